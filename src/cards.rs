@@ -1,8 +1,18 @@
 pub mod deck;
 pub mod hand;
+pub mod locale;
+pub mod notation;
+pub mod ordering;
+pub mod parse;
+pub mod physical;
 pub mod pile;
+pub mod render;
+pub mod set;
+pub mod shuffle;
+pub mod zone;
 
-use std::fmt::{Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::mem::transmute;
 use std::slice::Iter;
 
@@ -11,12 +21,16 @@ use std::slice::Iter;
 /// The playing card is internally represented by an unsigned 8-bit integer: \
 /// `00SSRRRR` \
 /// where `SS` stands for [Suit] (`00` for spades, `01` for hearts, `10` for diamonds, `11` for clubs)
-/// and `RRRR` stands for [Rank] (`0001` for ace, up to `1101` for king).
+/// and `RRRR` stands for [Rank] (`0001` for ace, up to `1101` for king, and `1110` for the knight
+/// of Tarot decks).
 ///
 /// A special case is for jokers, where `RRRR` is set to `1111` and the most significant bits can be
 /// used to differentiate the jokers. For up to three jokers, you can use [JokerColor] to differentiate
 /// red joker, black joker, and white joker.
-#[derive(Clone, Copy, Eq, PartialEq)]
+///
+/// The Tarot trumps set the highest bit instead: `1NNNNNNN`, where `NNNNNNN` is the number
+/// of the trump from 1 to 21, or 0 for [the Fool](Card::FOOL).
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
 pub struct Card(u8);
 
 impl Debug for Card {
@@ -25,6 +39,62 @@ impl Debug for Card {
     }
 }
 
+impl TryFrom<u8> for Card {
+    type Error = CardError;
+
+    /// Validates the raw encoding described in [Card].
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let card = Card(value);
+        let valid = if value & TRUMP_FLAG != 0 {
+            value & !TRUMP_FLAG <= TRUMP_COUNT
+        } else if card.is_joker() {
+            JokerColor::try_from(value >> 4).is_ok()
+        } else {
+            Suit::try_from(value >> 4).is_ok() && Rank::try_from(value & 0b1111).is_ok()
+        };
+        if valid {
+            Ok(card)
+        } else {
+            Err(CardError::InvalidEncoding(value))
+        }
+    }
+}
+
+impl From<Card> for u8 {
+    fn from(card: Card) -> Self {
+        card.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CardError {
+    InvalidSuit(u8),
+    InvalidRank(u8),
+    InvalidJokerColor(u8),
+    /// Trumps are numbered from 1 to 21.
+    InvalidTrump(u8),
+    /// The value doesn't encode any card.
+    InvalidEncoding(u8),
+}
+
+impl Display for CardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardError::InvalidSuit(value) => write!(f, "{} is not a valid suit.", value),
+            CardError::InvalidRank(value) => write!(f, "{} is not a valid rank.", value),
+            CardError::InvalidJokerColor(value) => {
+                write!(f, "{} is not a valid joker color.", value)
+            }
+            CardError::InvalidTrump(value) => write!(f, "{} is not a valid trump.", value),
+            CardError::InvalidEncoding(value) => {
+                write!(f, "{:#010b} doesn't encode a card.", value)
+            }
+        }
+    }
+}
+
+impl Error for CardError {}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Suit {
@@ -35,13 +105,13 @@ pub enum Suit {
 }
 
 impl TryFrom<u8> for Suit {
-    type Error = ();
+    type Error = CardError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         if (0..=3).contains(&value) {
-            Ok(unsafe { transmute(value) })
+            Ok(unsafe { transmute::<u8, Self>(value) })
         } else {
-            Err(())
+            Err(CardError::InvalidSuit(value))
         }
     }
 }
@@ -89,8 +159,10 @@ impl Suit {
     }
 }
 
+/// The rank of a suited card. Ranks are ordered from ace to king, with the
+/// [knight](Rank::Knight) between the jack and the queen.
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Rank {
     Ace = 1,
     Two,
@@ -105,21 +177,37 @@ pub enum Rank {
     Jack,
     Queen,
     King,
+    /// The fourth face card of Tarot decks. It's not part of a standard deck,
+    /// so [Rank::iter] skips it.
+    Knight,
+}
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.position().cmp(&other.position())
+    }
+}
+
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl TryFrom<u8> for Rank {
-    type Error = ();
+    type Error = CardError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        if ((Rank::Ace as u8)..=(Rank::King as u8)).contains(&value) {
-            Ok(unsafe { transmute(value) })
+        if ((Rank::Ace as u8)..=(Rank::Knight as u8)).contains(&value) {
+            Ok(unsafe { transmute::<u8, Self>(value) })
         } else {
-            Err(())
+            Err(CardError::InvalidRank(value))
         }
     }
 }
 
 impl Rank {
+    /// Iterates over the 13 ranks of a standard deck, from ace to king.
     pub fn iter() -> Iter<'static, Rank> {
         static RANKS: [Rank; 13] = [
             Rank::Ace,
@@ -139,6 +227,37 @@ impl Rank {
         RANKS.iter()
     }
 
+    /// Iterates over the 14 ranks of a Tarot deck in order, with the knight
+    /// between the jack and the queen.
+    pub fn iter_with_knight() -> Iter<'static, Rank> {
+        static RANKS: [Rank; 14] = [
+            Rank::Ace,
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Jack,
+            Rank::Knight,
+            Rank::Queen,
+            Rank::King,
+        ];
+        RANKS.iter()
+    }
+
+    /// Returns the place of the rank in [Rank::iter_with_knight], from 0 for aces to 13 for kings.
+    pub(crate) const fn position(self) -> usize {
+        match self {
+            Rank::Knight => Rank::Jack as usize,
+            Rank::Queen | Rank::King => self as usize,
+            _ => self as usize - 1,
+        }
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Rank::Ace => "ace",
@@ -154,6 +273,7 @@ impl Rank {
             Rank::Jack => "jack",
             Rank::Queen => "queen",
             Rank::King => "king",
+            Rank::Knight => "knight",
         }
     }
 
@@ -172,6 +292,7 @@ impl Rank {
             Rank::Jack => "J",
             Rank::Queen => "Q",
             Rank::King => "K",
+            Rank::Knight => "C",
         }
     }
 }
@@ -185,18 +306,23 @@ pub enum JokerColor {
 }
 
 impl TryFrom<u8> for JokerColor {
-    type Error = ();
+    type Error = CardError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         if (1..=3).contains(&value) {
-            Ok(unsafe { transmute(value) })
+            Ok(unsafe { transmute::<u8, Self>(value) })
         } else {
-            Err(())
+            Err(CardError::InvalidJokerColor(value))
         }
     }
 }
 
 impl JokerColor {
+    pub fn iter() -> Iter<'static, JokerColor> {
+        static COLORS: [JokerColor; 3] = [JokerColor::Red, JokerColor::Black, JokerColor::White];
+        COLORS.iter()
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             JokerColor::Red => "red",
@@ -206,7 +332,18 @@ impl JokerColor {
     }
 }
 
+/// Marks the encoding of a Tarot trump, see [Card].
+const TRUMP_FLAG: u8 = 0b1000_0000;
+const TRUMP_COUNT: u8 = 21;
+
+/// The card back, for showing cards face down.
+pub const CARD_BACK_UNICODE: char = '🂠';
+
 impl Card {
+    /// The Fool, or Excuse, of Tarot decks. It isn't a trump,
+    /// but is written like trump 0, its place among the Unicode Tarot cards.
+    pub const FOOL: Card = Card(TRUMP_FLAG);
+
     #[inline]
     pub fn new(suit: Suit, rank: Rank) -> Self {
         Card(((suit as u8) << 4) | (rank as u8))
@@ -217,18 +354,51 @@ impl Card {
         Card(((color as u8) << 4) | 0b1111)
     }
 
-    /// Returns whether this card is a standard card (i.e. not joker).
+    /// Returns one of the 21 Tarot trumps.
+    pub fn new_trump(number: u8) -> Result<Self, CardError> {
+        if (1..=TRUMP_COUNT).contains(&number) {
+            Ok(Card(TRUMP_FLAG | number))
+        } else {
+            Err(CardError::InvalidTrump(number))
+        }
+    }
+
+    /// Returns whether this card is one of the 52 cards of a standard deck
+    /// (i.e. not a joker, knight or trump).
     pub fn is_standard_card(&self) -> bool {
-        (self.0 & 0b1111) <= (Rank::King as u8)
+        !self.is_tarot_only() && (self.0 & 0b1111) <= (Rank::King as u8)
     }
 
     /// Returns whether this card is a joker.
     pub fn is_joker(&self) -> bool {
-        (self.0 & 0b1111) == 0b1111
+        !self.is_tarot_only() && (self.0 & 0b1111) == 0b1111
+    }
+
+    pub fn is_knight(&self) -> bool {
+        !self.is_tarot_only() && (self.0 & 0b1111) == Rank::Knight as u8
+    }
+
+    /// Returns whether this card is one of the 21 Tarot trumps. The Fool isn't one.
+    pub fn is_trump(&self) -> bool {
+        self.is_tarot_only() && *self != Card::FOOL
+    }
+
+    pub fn is_fool(&self) -> bool {
+        *self == Card::FOOL
+    }
+
+    /// Returns whether this card has a suit and a rank, i.e. is a standard card or a knight.
+    pub fn is_suited(&self) -> bool {
+        self.is_standard_card() || self.is_knight()
+    }
+
+    /// Returns whether this card is a trump or the Fool.
+    fn is_tarot_only(&self) -> bool {
+        self.0 & TRUMP_FLAG != 0
     }
 
     pub fn suit(&self) -> Option<Suit> {
-        if self.is_standard_card() {
+        if self.is_suited() {
             Suit::try_from(self.0 >> 4).ok()
         } else {
             None
@@ -236,7 +406,7 @@ impl Card {
     }
 
     pub fn rank(&self) -> Option<Rank> {
-        if self.is_standard_card() {
+        if self.is_suited() {
             Rank::try_from(self.0 & 0b1111).ok()
         } else {
             None
@@ -251,54 +421,127 @@ impl Card {
         }
     }
 
-    pub fn to_unicode(&self) -> Result<char, ()> {
-        if self.is_standard_card() {
-            let suit_val = self.suit().ok_or(())? as u8;
-            let mut rank_val = self.rank().ok_or(())? as u8;
-            if rank_val > Rank::Jack as u8 {
-                rank_val += 1; // Unicode also has rare Knight card suit between jack and queen
-            }
+    /// Returns the number of the trump, from 1 to 21.
+    pub fn trump_number(&self) -> Option<u8> {
+        if self.is_trump() {
+            Some(self.0 & !TRUMP_FLAG)
+        } else {
+            None
+        }
+    }
+
+    pub fn to_unicode(self) -> Result<char, CardError> {
+        if self.is_tarot_only() {
+            let value = 0x1F0E0 + (self.0 & !TRUMP_FLAG) as u32;
+            Ok(char::from_u32(value).ok_or(CardError::InvalidEncoding(self.0))?)
+        } else if self.is_suited() {
+            let suit_val = self.suit().ok_or(CardError::InvalidEncoding(self.0))? as u8;
+            let rank = self.rank().ok_or(CardError::InvalidEncoding(self.0))?;
+            // Unicode has the knight between the jack and the queen
+            let rank_val = rank.position() as u8 + 1;
             let mut value: u32 = 0x1F0A0;
             value += (suit_val << 4) as u32;
             value += rank_val as u32;
-            Ok(char::from_u32(value).ok_or(())?)
+            Ok(char::from_u32(value).ok_or(CardError::InvalidEncoding(self.0))?)
         } else {
-            let joker_color = self.joker_color().ok_or(())? as u8;
+            let joker_color = self
+                .joker_color()
+                .ok_or(CardError::InvalidEncoding(self.0))? as u8;
             let mut value: u32 = 0x1F0AF;
             value += (joker_color << 4) as u32;
-            Ok(char::from_u32(value).ok_or(())?)
+            Ok(char::from_u32(value).ok_or(CardError::InvalidEncoding(self.0))?)
         }
     }
 
-    pub fn to_suit_rank(&self) -> Result<String, ()> {
-        if self.is_standard_card() {
-            let suit = self.suit().ok_or(())?.unicode_black();
-            let rank = self.rank().ok_or(())?.index();
+    /// Returns the card represented by the Unicode playing card. The card back isn't a card.
+    pub fn from_unicode(c: char) -> Option<Card> {
+        let offset = (c as u32).checked_sub(0x1F0A0)?;
+        if offset >= 0x56 {
+            return None;
+        }
+        if offset >= 0x40 {
+            return match offset as u8 - 0x40 {
+                0 => Some(Card::FOOL),
+                number => Card::new_trump(number).ok(),
+            };
+        }
+        let (high, low) = ((offset >> 4) as u8, (offset & 0b1111) as u8);
+        match low {
+            0b1111 => JokerColor::try_from(high).ok().map(Card::new_joker),
+            0 => None,
+            _ => Some(Card::new(
+                Suit::try_from(high).ok()?,
+                *Rank::iter_with_knight().nth(low as usize - 1)?,
+            )),
+        }
+    }
+
+    /// Returns the card written as the suit symbol followed by the rank index, like `♦K`.
+    /// Jokers are written as `🃏1` to `🃏3`, and trumps like in [Card::to_ascii].
+    pub fn to_suit_rank(self) -> Result<String, CardError> {
+        if self.is_tarot_only() {
+            self.to_ascii()
+        } else if self.is_suited() {
+            let suit = self
+                .suit()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .unicode_black();
+            let rank = self
+                .rank()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .index();
             Ok(format!("{}{}", suit, rank))
         } else {
-            let joker_color = self.joker_color().ok_or(())? as u8;
+            let joker_color = self
+                .joker_color()
+                .ok_or(CardError::InvalidEncoding(self.0))? as u8;
             Ok(format!("🃏{}", joker_color))
         }
     }
 
-    pub fn to_ascii(&self) -> Result<String, ()> {
-        if self.is_standard_card() {
-            let suit = self.suit().ok_or(())?.letter();
-            let rank = self.rank().ok_or(())?.index();
+    /// Returns the card written as the rank index followed by the suit letter, like `Kd`.
+    /// Jokers are written as `J1` to `J3`, trumps as `T1` to `T21`, and the Fool as `T0`.
+    pub fn to_ascii(self) -> Result<String, CardError> {
+        if self.is_tarot_only() {
+            Ok(format!("T{}", self.0 & !TRUMP_FLAG))
+        } else if self.is_suited() {
+            let suit = self
+                .suit()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .letter();
+            let rank = self
+                .rank()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .index();
             Ok(format!("{}{}", rank, suit))
         } else {
-            let joker_color = self.joker_color().ok_or(())? as u8;
+            let joker_color = self
+                .joker_color()
+                .ok_or(CardError::InvalidEncoding(self.0))? as u8;
             Ok(format!("J{}", joker_color))
         }
     }
 
-    pub fn name(&self) -> Result<String, ()> {
-        if self.is_standard_card() {
-            let suit_name = self.suit().ok_or(())?.name();
-            let rank_name = self.rank().ok_or(())?.name();
+    pub fn name(&self) -> Result<String, CardError> {
+        if self.is_fool() {
+            Ok("fool".to_string())
+        } else if let Some(number) = self.trump_number() {
+            Ok(format!("trump {}", number))
+        } else if self.is_suited() {
+            let suit_name = self
+                .suit()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .name();
+            let rank_name = self
+                .rank()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .name();
             Ok(format!("{} of {}", rank_name, suit_name))
         } else {
-            let color_name = self.joker_color().ok_or(())?.name();
+            let color_name = self
+                .joker_color()
+                .ok_or(CardError::InvalidEncoding(self.0))?
+                .name();
             Ok(format!("{} joker", color_name))
         }
     }
@@ -311,7 +554,7 @@ mod tests {
     #[test]
     fn construct() {
         let card1 = Card::new(Suit::Diamonds, Rank::King);
-        let card2 = Card { 0: 0b10_1101 };
+        let card2 = Card(0b10_1101);
         assert_eq!(card1, card2);
         assert!(card1.is_standard_card());
         assert_eq!(card1.suit(), Some(Suit::Diamonds));
@@ -335,4 +578,77 @@ mod tests {
         assert_eq!(card2.to_ascii().unwrap(), "J2");
         assert_eq!(card2.name().unwrap(), "black joker");
     }
+
+    #[test]
+    fn encoding() {
+        let card = Card::new(Suit::Clubs, Rank::Ten);
+        assert_eq!(Card::try_from(u8::from(card)), Ok(card));
+        let joker = Card::new_joker(JokerColor::White);
+        assert_eq!(Card::try_from(u8::from(joker)), Ok(joker));
+
+        assert_eq!(Card::try_from(0), Err(CardError::InvalidEncoding(0)));
+        assert_eq!(
+            Card::try_from(0b00_1111),
+            Err(CardError::InvalidEncoding(0b00_1111))
+        );
+        assert_eq!(
+            Card::try_from(0b01_0000),
+            Err(CardError::InvalidEncoding(0b01_0000))
+        );
+        assert_eq!(
+            Card::try_from(0b1001_0110),
+            Err(CardError::InvalidEncoding(0b1001_0110))
+        );
+        assert_eq!(
+            Card::try_from(0b100_0001),
+            Err(CardError::InvalidEncoding(0b100_0001))
+        );
+        assert_eq!(Suit::try_from(4), Err(CardError::InvalidSuit(4)));
+        assert_eq!(Rank::try_from(15), Err(CardError::InvalidRank(15)));
+
+        let knight = Card::new(Suit::Hearts, Rank::Knight);
+        assert_eq!(Card::try_from(0b01_1110), Ok(knight));
+        let trump = Card::new_trump(21).unwrap();
+        assert_eq!(Card::try_from(u8::from(trump)), Ok(trump));
+        assert_eq!(Card::try_from(u8::from(Card::FOOL)), Ok(Card::FOOL));
+    }
+
+    #[test]
+    fn tarot() {
+        let knight = Card::new(Suit::Spades, Rank::Knight);
+        assert!(knight.is_knight() && knight.is_suited());
+        assert!(!knight.is_standard_card() && !knight.is_joker());
+        assert_eq!(knight.suit(), Some(Suit::Spades));
+        assert_eq!(knight.to_unicode().unwrap(), '🂬');
+        assert_eq!(knight.to_suit_rank().unwrap(), "♠C");
+        assert_eq!(knight.to_ascii().unwrap(), "Cs");
+        assert_eq!(knight.name().unwrap(), "knight of spades");
+        assert_eq!(Card::from_unicode('🂬'), Some(knight));
+        assert_eq!(
+            Card::new(Suit::Spades, Rank::Queen).to_unicode().unwrap(),
+            '🂭'
+        );
+        assert!(Rank::Jack < Rank::Knight && Rank::Knight < Rank::Queen);
+
+        let trump = Card::new_trump(21).unwrap();
+        assert!(trump.is_trump() && !trump.is_fool());
+        assert!(!trump.is_standard_card() && !trump.is_joker() && !trump.is_suited());
+        assert_eq!(trump.trump_number(), Some(21));
+        assert_eq!(trump.suit(), None);
+        assert_eq!(trump.to_unicode().unwrap(), '🃵');
+        assert_eq!(trump.to_ascii().unwrap(), "T21");
+        assert_eq!(trump.name().unwrap(), "trump 21");
+        assert_eq!(Card::from_unicode('🃵'), Some(trump));
+        assert_eq!(Card::new_trump(22), Err(CardError::InvalidTrump(22)));
+        assert_eq!(Card::new_trump(0), Err(CardError::InvalidTrump(0)));
+
+        assert!(Card::FOOL.is_fool() && !Card::FOOL.is_trump());
+        assert_eq!(Card::FOOL.trump_number(), None);
+        assert_eq!(Card::FOOL.to_unicode().unwrap(), '🃠');
+        assert_eq!(Card::FOOL.to_ascii().unwrap(), "T0");
+        assert_eq!(Card::FOOL.name().unwrap(), "fool");
+        assert_eq!(Card::from_unicode('🃠'), Some(Card::FOOL));
+        assert_eq!(Card::from_unicode(CARD_BACK_UNICODE), None);
+        assert_eq!(Card::from_unicode('🃶'), None);
+    }
 }
@@ -1,35 +1,227 @@
+use crate::cards::physical::PhysicalCard;
 use crate::cards::{Card, JokerColor, Rank, Suit};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
 
-pub fn generate_n_decks(n: usize, jokers: usize) -> Vec<Card> {
-    if jokers > 3 {
-        unimplemented!("Max number of jokers is 3.");
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DeckError {
+    /// A deck of [Card]s can have at most one joker of each [JokerColor].
+    /// Use [DeckSpec::generate_physical] for more.
+    TooManyJokers(usize),
+}
+
+impl Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckError::TooManyJokers(jokers) => {
+                write!(f, "A deck can't have {} jokers, the maximum is 3.", jokers)
+            }
+        }
     }
+}
 
-    let mut vec = Vec::with_capacity(n * (52 + jokers));
+impl Error for DeckError {}
 
-    for _deck in 0..n {
-        for suit in Suit::iter() {
-            for rank in Rank::iter() {
-                vec.push(Card::new(suit.clone(), *rank));
-            }
+/// Describes which cards make up a deck: its ranks, how many copies of it are shuffled
+/// together, how many jokers each copy has, and whether it has the Tarot trumps.
+///
+/// ```
+/// use cardrs::cards::deck::DeckSpec;
+/// use cardrs::cards::Rank;
+///
+/// let deck = DeckSpec::stripped(Rank::Seven).copies(2).jokers(1).generate().unwrap();
+/// assert_eq!(deck.len(), 66);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DeckSpec {
+    ranks: Vec<Rank>,
+    copies: usize,
+    jokers: usize,
+    trumps: bool,
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl DeckSpec {
+    /// A single 52-card deck without jokers.
+    pub fn standard() -> Self {
+        Self::with_ranks(Rank::iter().copied())
+    }
+
+    /// A deck with only the given ranks in every suit.
+    pub fn with_ranks<I: IntoIterator<Item = Rank>>(ranks: I) -> Self {
+        let mut ranks: Vec<Rank> = ranks.into_iter().collect();
+        ranks.sort();
+        ranks.dedup();
+        DeckSpec {
+            ranks,
+            copies: 1,
+            jokers: 0,
+            trumps: false,
         }
+    }
+
+    /// A 78-card Tarot deck: 14 ranks with the knight in every suit, 21 trumps and the Fool.
+    pub fn tarot() -> Self {
+        Self::with_ranks(Rank::iter_with_knight().copied()).trumps(true)
+    }
+
+    /// A deck with the ranks from `lowest` to king, and aces.
+    pub fn stripped(lowest: Rank) -> Self {
+        Self::with_ranks(
+            Rank::iter()
+                .copied()
+                .filter(|&rank| rank == Rank::Ace || rank >= lowest),
+        )
+    }
+
+    /// Returns one of the common deck sizes:
+    /// 24 (nines to aces), 32 (piquet, sevens to aces), 36 (sixes to aces),
+    /// 40 (aces to sevens and face cards, like the Italian and Spanish decks), 52 or 78 (Tarot).
+    pub fn of_size(size: usize) -> Option<Self> {
+        match size {
+            24 => Some(Self::stripped(Rank::Nine)),
+            32 => Some(Self::stripped(Rank::Seven)),
+            36 => Some(Self::stripped(Rank::Six)),
+            40 => Some(Self::with_ranks(
+                Rank::iter()
+                    .copied()
+                    .filter(|&rank| !(Rank::Eight..=Rank::Ten).contains(&rank)),
+            )),
+            52 => Some(Self::standard()),
+            78 => Some(Self::tarot()),
+            _ => None,
+        }
+    }
+
+    /// Sets the number of decks shuffled together.
+    pub fn copies(mut self, copies: usize) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    /// Sets the number of jokers in each copy. [DeckSpec::generate] allows at most 3.
+    pub fn jokers(mut self, jokers: usize) -> Self {
+        self.jokers = jokers;
+        self
+    }
 
-        if jokers >= 1 {
-            vec.push(Card::new_joker(JokerColor::Red));
+    /// Sets whether each copy has the 21 trumps and the Fool.
+    pub fn trumps(mut self, trumps: bool) -> Self {
+        self.trumps = trumps;
+        self
+    }
+
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+
+    pub fn has_rank(&self, rank: Rank) -> bool {
+        self.ranks.contains(&rank)
+    }
+
+    pub fn has_jokers(&self) -> bool {
+        self.copies > 0 && self.jokers > 0
+    }
 
-            if jokers >= 2 {
-                vec.push(Card::new_joker(JokerColor::Black));
+    pub fn has_trumps(&self) -> bool {
+        self.copies > 0 && self.trumps
+    }
+
+    /// Returns the number of cards in all copies, jokers included.
+    pub fn card_count(&self) -> usize {
+        let trumps = if self.trumps { 22 } else { 0 };
+        self.copies * (4 * self.ranks.len() + self.jokers + trumps)
+    }
+
+    /// Generates the cards of every copy in order: suits, ranks within each suit,
+    /// then the jokers of that copy, and the Fool and trumps 1 to 21.
+    pub fn generate(&self) -> Result<Vec<Card>, DeckError> {
+        if self.jokers > 3 {
+            return Err(DeckError::TooManyJokers(self.jokers));
+        }
+        Ok(self.faces())
+    }
 
-                if jokers >= 3 {
-                    vec.push(Card::new_joker(JokerColor::White));
+    /// Generates the cards like [DeckSpec::generate], numbering the copies of each face.
+    ///
+    /// Any number of jokers is allowed: they cycle through the [JokerColor]s, so the fourth
+    /// joker of a deck is another copy of the red one.
+    pub fn generate_physical(&self) -> Vec<PhysicalCard> {
+        PhysicalCard::tag_all(self.faces())
+    }
+
+    fn faces(&self) -> Vec<Card> {
+        let mut vec = Vec::with_capacity(self.card_count());
+        for _deck in 0..self.copies {
+            for &suit in Suit::iter() {
+                for &rank in &self.ranks {
+                    vec.push(Card::new(suit, rank));
                 }
             }
+            vec.extend(
+                JokerColor::iter()
+                    .cycle()
+                    .take(self.jokers)
+                    .map(|&color| Card::new_joker(color)),
+            );
+            if self.trumps {
+                vec.push(Card::FOOL);
+                vec.extend((1..=21).map(|number| Card::new_trump(number).unwrap()));
+            }
         }
+        vec
     }
+}
 
-    vec
+pub fn generate_n_decks(n: usize, jokers: usize) -> Result<Vec<Card>, DeckError> {
+    DeckSpec::standard().copies(n).jokers(jokers).generate()
 }
 
-pub fn generate_deck(jokers: usize) -> Vec<Card> {
+pub fn generate_deck(jokers: usize) -> Result<Vec<Card>, DeckError> {
     generate_n_decks(1, jokers)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deck_sizes() {
+        for size in [24, 32, 36, 40, 52, 78] {
+            let spec = DeckSpec::of_size(size).unwrap();
+            assert_eq!(spec.card_count(), size);
+            assert_eq!(spec.generate().unwrap().len(), size);
+        }
+        assert_eq!(DeckSpec::of_size(50), None);
+
+        let piquet = DeckSpec::of_size(32).unwrap();
+        assert!(piquet.has_rank(Rank::Ace));
+        assert!(!piquet.has_rank(Rank::Six));
+        assert!(!DeckSpec::of_size(40).unwrap().has_rank(Rank::Ten));
+
+        let deck = DeckSpec::stripped(Rank::King)
+            .copies(2)
+            .jokers(3)
+            .generate()
+            .unwrap();
+        assert_eq!(deck.len(), 22);
+        assert_eq!(deck[8], Card::new_joker(JokerColor::Red));
+        assert_eq!(
+            DeckSpec::standard().jokers(4).generate(),
+            Err(DeckError::TooManyJokers(4))
+        );
+
+        let tarot = DeckSpec::tarot().generate().unwrap();
+        assert_eq!(tarot[11], Card::new(Suit::Spades, Rank::Knight));
+        assert_eq!(tarot[56], Card::FOOL);
+        assert_eq!(tarot[77], Card::new_trump(21).unwrap());
+        assert!(DeckSpec::tarot().has_trumps());
+        assert_eq!(DeckSpec::tarot().trumps(false).card_count(), 56);
+    }
+}
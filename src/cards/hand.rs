@@ -1,15 +1,22 @@
-use crate::cards::Card;
+use crate::cards::notation::{Notation, NotationDisplay};
+use crate::cards::ordering::CardOrdering;
+use crate::cards::physical::PhysicalCard;
+use crate::cards::set::{CardCounts, CardSet};
+use crate::cards::zone::{Take, Zone, ZoneError, WRONG_SNAPSHOT};
+use crate::cards::{Card, Rank, Suit};
 use sorted_vec::SortedVec;
+use std::any::Any;
 use std::fmt;
 
 /// Standard wrapper for a card that implements [Ord] and [PartialOrd].
 ///
 /// This is used to sort cards in a hand. The sorting order is:
-/// 1. Aces to kings of clubs.
-/// 2. Aces to kings of diamonds.
-/// 3. Aces to kings of hearts.
-/// 4. Aces to kings of spades.
+/// 1. Aces to kings of spades, with the knight between the jack and the queen.
+/// 2. Aces to kings of hearts.
+/// 3. Aces to kings of diamonds.
+/// 4. Aces to kings of clubs.
 /// 5. Jokers, in the order red, black, white.
+/// 6. The Fool, then trumps 1 to 21.
 ///
 /// This isn't necessarily the only way to sort cards, which is why this is a separate type,
 /// and [Card] itself doesn't implement [Ord] or [PartialOrd].
@@ -22,42 +29,26 @@ impl From<Card> for SortedCard {
     }
 }
 
-impl Into<Card> for SortedCard {
-    fn into(self) -> Card {
-        self.0
+impl From<SortedCard> for Card {
+    fn from(card: SortedCard) -> Self {
+        card.0
     }
 }
 
-impl Ord for SortedCard {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.0 == other.0 {
-            return std::cmp::Ordering::Equal;
-        }
-
-        // Two jokers
-        if self.0.is_joker() && other.0.is_joker() {
-            return (self.0.joker_color().unwrap() as u8)
-                .cmp(&(other.0.joker_color().unwrap() as u8));
-        }
-
-        // One joker
-        if self.0.is_joker() {
-            return std::cmp::Ordering::Greater;
-        } else if other.0.is_joker() {
-            return std::cmp::Ordering::Less;
+impl SortedCard {
+    /// Returns the group of the card (suited cards, jokers, Tarot cards) and its place in it.
+    fn key(&self) -> (u8, u8, usize) {
+        match (self.0.suit(), self.0.rank(), self.0.joker_color()) {
+            (Some(suit), Some(rank), _) => (0, suit as u8, rank.position()),
+            (_, _, Some(color)) => (1, color as u8, 0),
+            _ => (2, 0, self.0.trump_number().unwrap_or(0) as usize),
         }
+    }
+}
 
-        // Standard cards
-        let self_suit = self.0.suit().unwrap();
-        let other_suit = other.0.suit().unwrap();
-        let self_rank = self.0.rank().unwrap();
-        let other_rank = other.0.rank().unwrap();
-
-        if self_suit == other_suit {
-            self_rank.cmp(&other_rank)
-        } else {
-            (self_suit as u8).cmp(&(other_suit as u8))
-        }
+impl Ord for SortedCard {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
     }
 }
 
@@ -70,31 +61,152 @@ impl PartialOrd for SortedCard {
 /// Represents a hand of cards.
 ///
 /// It's always sorted in the order defined by `T`.
-/// If unsure, use [SortedCard] as `T`.
-#[derive(Debug)]
+/// If unsure, use [SortedCard] as `T`, or [PhysicalCard] to tell identical cards apart.
+///
+/// Alongside the sorted cards it keeps their [CardCounts], so membership queries don't
+/// need to scan the hand.
+#[derive(Debug, Clone)]
 pub struct Hand<T: Ord> {
     cards: SortedVec<T>,
+    counts: CardCounts,
 }
 
-impl<T: Ord + From<Card> + Into<Card> + Clone> Hand<T> {
+impl<T: Ord + Into<Card> + Clone> Hand<T> {
     pub fn new() -> Self {
         Hand {
             cards: SortedVec::new(),
+            counts: CardCounts::new(),
         }
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
         Hand {
             cards: SortedVec::with_capacity(capacity),
+            counts: CardCounts::new(),
         }
     }
 
-    pub fn add_card(&mut self, card: Card) {
-        self.cards.insert(card.into());
+    /// Adds the card, which can be a [Card] for hands of [SortedCard]s.
+    pub fn add_card<C: Into<T>>(&mut self, card: C) {
+        let card = card.into();
+        self.counts.insert(card.clone().into());
+        self.cards.insert(card);
+    }
+
+    /// Removes one copy of the card, returning whether it was in the hand.
+    /// In a hand of [PhysicalCard]s only that exact copy is removed.
+    pub fn remove_card<C: Into<T>>(&mut self, card: C) -> bool {
+        let Some(card) = self.cards.remove_item(&card.into()) else {
+            return false;
+        };
+        self.counts.remove(card.into());
+        true
+    }
+
+    /// Removes any one card with the face, returning it if there was one.
+    pub fn remove_face(&mut self, face: Card) -> Option<T> {
+        let position = self
+            .cards
+            .iter()
+            .position(|card| card.clone().into() == face)?;
+        self.counts.remove(face);
+        Some(self.cards.remove_index(position))
+    }
+
+    /// Removes and returns all cards whose face matches the predicate, in the hand's order.
+    pub fn take_where<F: FnMut(Card) -> bool>(&mut self, mut predicate: F) -> Vec<T> {
+        let mut taken = Vec::new();
+        self.cards.retain(|card| {
+            if predicate(card.clone().into()) {
+                taken.push(card.clone());
+                false
+            } else {
+                true
+            }
+        });
+        for card in &taken {
+            self.counts.remove(card.clone().into());
+        }
+        taken
     }
 
-    pub fn remove_card(&mut self, card: Card) {
-        self.cards.remove_item(&card.into());
+    /// Returns the first `n` cards in the hand's order.
+    fn first_n(&self, n: usize) -> Result<Vec<T>, ZoneError> {
+        if n > self.len() {
+            return Err(ZoneError::NotEnoughCards {
+                requested: n,
+                available: self.len(),
+            });
+        }
+        Ok(self.cards[..n].to_vec())
+    }
+
+    /// Removes one of each card, or none of them if any is missing.
+    fn remove_all(&mut self, cards: &[T]) -> Result<(), ZoneError> {
+        let mut remaining = self.cards.clone();
+        for card in cards {
+            if remaining.remove_item(card).is_none() {
+                return Err(ZoneError::CardMissing(card.clone().into()));
+            }
+        }
+        self.cards = remaining;
+        for card in cards {
+            self.counts.remove(card.clone().into());
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.counts.contains(card)
+    }
+
+    /// Returns the number of copies of the card in the hand.
+    pub fn count(&self, card: Card) -> usize {
+        self.counts.count(card)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn cards_of_suit(&self, suit: Suit) -> Vec<Card> {
+        self.iter()
+            .filter(|card| card.suit() == Some(suit))
+            .collect()
+    }
+
+    pub fn cards_of_rank(&self, rank: Rank) -> Vec<Card> {
+        self.iter()
+            .filter(|card| card.rank() == Some(rank))
+            .collect()
+    }
+
+    pub fn jokers(&self) -> Vec<Card> {
+        self.iter().filter(|card| card.is_joker()).collect()
+    }
+
+    /// Returns the number of cards of each suit, indexed by `Suit as usize`.
+    /// Jokers and trumps aren't counted.
+    pub fn suit_histogram(&self) -> [usize; 4] {
+        let mut histogram = [0; 4];
+        for suit in self.iter().filter_map(|card| card.suit()) {
+            histogram[suit as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Returns the number of cards of each rank in rank order, from aces to kings with
+    /// knights between jacks and queens. Jokers and trumps aren't counted.
+    pub fn rank_histogram(&self) -> [usize; 14] {
+        let mut histogram = [0; 14];
+        for rank in self.iter().filter_map(|card| card.rank()) {
+            histogram[rank.position()] += 1;
+        }
+        histogram
     }
 
     pub fn cards(&self) -> &SortedVec<T> {
@@ -104,25 +216,170 @@ impl<T: Ord + From<Card> + Into<Card> + Clone> Hand<T> {
     pub fn iter(&self) -> impl Iterator<Item = Card> + '_ {
         self.cards.iter().cloned().map(|card| card.into())
     }
+
+    /// Returns the distinct cards in the hand other than trumps and the Fool, which are in
+    /// [CardCounts::tarot_set].
+    pub fn card_set(&self) -> CardSet {
+        self.counts.set()
+    }
+
+    /// Returns the cards in the hand with the number of copies of each.
+    pub fn card_counts(&self) -> &CardCounts {
+        &self.counts
+    }
+
+    /// Returns the cards sorted by the runtime ordering instead of `T`.
+    pub fn sorted_by(&self, ordering: &CardOrdering) -> Vec<Card> {
+        let mut cards: Vec<Card> = self.iter().collect();
+        ordering.sort(&mut cards);
+        cards
+    }
 }
 
-impl<T: Ord + From<Card> + Into<Card> + Clone> fmt::Display for Hand<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<T: Ord + Into<Card> + Clone> Default for Hand<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Into<Card> + Clone> NotationDisplay for Hand<T> {
+    /// Writes the cards separated by commas, padding the whole list according to the format spec.
+    fn fmt_notation(&self, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result {
         let cards: Vec<String> = self
             .iter()
-            .map(|card| card.to_suit_rank().unwrap_or_else(|()| "??".to_string()))
+            .map(|card| card.display(notation).to_string())
             .collect();
-        write!(f, "{}", cards.join(", "))
+        f.pad(&cards.join(", "))
+    }
+}
+
+impl<T: Ord + Into<Card> + Clone> fmt::Display for Hand<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_notation(f, Notation::from_formatter(f))
+    }
+}
+
+impl<T: Ord + From<Card> + Into<Card> + Clone + 'static> Zone for Hand<T> {
+    fn take(&mut self, take: Take) -> Result<Vec<Card>, ZoneError> {
+        let cards = match take {
+            Take::Top(n) => self.first_n(n)?,
+            Take::Cards(cards) => cards.iter().map(|&card| card.into()).collect(),
+        };
+        self.remove_all(&cards)?;
+        Ok(cards.into_iter().map(|card| card.into()).collect())
+    }
+
+    fn put(&mut self, cards: Vec<Card>) {
+        for card in cards {
+            self.add_card(card);
+        }
+    }
+
+    fn card_counts(&self) -> CardCounts {
+        self.counts.clone()
+    }
+
+    fn snapshot(&self) -> Box<dyn Any> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Box<dyn Any>) {
+        *self = *snapshot.downcast().expect(WRONG_SNAPSHOT);
+    }
+}
+
+/// Takes and puts the exact copies of the cards.
+impl Zone<PhysicalCard> for Hand<PhysicalCard> {
+    fn take(&mut self, take: Take<PhysicalCard>) -> Result<Vec<PhysicalCard>, ZoneError> {
+        let cards = match take {
+            Take::Top(n) => self.first_n(n)?,
+            Take::Cards(cards) => cards.to_vec(),
+        };
+        self.remove_all(&cards)?;
+        Ok(cards)
+    }
+
+    fn put(&mut self, cards: Vec<PhysicalCard>) {
+        for card in cards {
+            self.add_card(card);
+        }
+    }
+
+    fn card_counts(&self) -> CardCounts {
+        self.counts.clone()
+    }
+
+    fn snapshot(&self) -> Box<dyn Any> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Box<dyn Any>) {
+        *self = *snapshot.downcast().expect(WRONG_SNAPSHOT);
     }
 }
 
 pub trait HasHand {
-    type CardType: Ord + From<Card> + Into<Card> + Clone;
+    type CardType: Ord + Into<Card> + Clone;
 
     fn hand(&self) -> &Hand<Self::CardType>;
     fn hand_mut(&mut self) -> &mut Hand<Self::CardType>;
 
-    fn deal(&mut self, card: Card) {
+    fn deal<C: Into<Self::CardType>>(&mut self, card: C)
+    where
+        Self: Sized,
+    {
         self.hand_mut().add_card(card);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::generate_n_decks;
+
+    #[test]
+    fn queries() {
+        let mut hand: Hand<SortedCard> = Hand::new();
+        for card in generate_n_decks(2, 1).unwrap() {
+            if card.is_joker() || card.rank() <= Some(Rank::Two) {
+                hand.add_card(card);
+            }
+        }
+        let ace = Card::new(Suit::Hearts, Rank::Ace);
+        assert_eq!(hand.len(), 18);
+        assert_eq!(hand.count(ace), 2);
+        assert_eq!(
+            hand.cards_of_suit(Suit::Hearts),
+            vec![
+                ace,
+                ace,
+                Card::new(Suit::Hearts, Rank::Two),
+                Card::new(Suit::Hearts, Rank::Two)
+            ]
+        );
+        assert_eq!(hand.cards_of_rank(Rank::Ace).len(), 8);
+        assert_eq!(hand.jokers().len(), 2);
+        assert_eq!(hand.suit_histogram(), [4; 4]);
+        assert_eq!(hand.rank_histogram()[..3], [8, 8, 0]);
+
+        let mut court: Hand<SortedCard> = Hand::new();
+        for rank in [Rank::Jack, Rank::Knight, Rank::Knight, Rank::Queen] {
+            court.add_card(Card::new(Suit::Clubs, rank));
+        }
+        assert_eq!(court.rank_histogram()[10..], [1, 2, 1, 0]);
+
+        assert!(hand.remove_card(ace));
+        assert!(hand.contains(ace));
+        assert!(hand.remove_card(ace));
+        assert!(!hand.contains(ace));
+        assert!(!hand.remove_card(ace));
+
+        let jokers = hand.take_where(|card| card.is_joker());
+        assert_eq!(jokers.len(), 2);
+        assert!(hand.jokers().is_empty());
+        assert_eq!(hand.count(jokers[0].into()), 0);
+        assert_eq!(hand.len(), 14);
+        hand.take_where(|_| true);
+        assert!(hand.is_empty());
+    }
+}
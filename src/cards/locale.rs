@@ -0,0 +1,265 @@
+use crate::cards::parse::ParseCardError;
+use crate::cards::set::TarotSet;
+use crate::cards::{Card, JokerColor, Rank, Suit};
+use std::str::FromStr;
+
+/// Grammatical case a card name is used in within a sentence.
+/// Languages without cases, like English, ignore it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GrammaticalCase {
+    /// As the subject, e.g. "*dama kier* leży na stosie".
+    Nominative,
+    /// E.g. "brak *damy kier*".
+    Genitive,
+    /// As the object, e.g. "zagrywa *damę kier*".
+    Accusative,
+}
+
+impl GrammaticalCase {
+    pub fn iter() -> std::slice::Iter<'static, GrammaticalCase> {
+        static CASES: [GrammaticalCase; 3] = [
+            GrammaticalCase::Nominative,
+            GrammaticalCase::Genitive,
+            GrammaticalCase::Accusative,
+        ];
+        CASES.iter()
+    }
+}
+
+/// Language-specific names of cards, used for displaying and parsing them.
+pub trait Locale {
+    fn suit_name(&self, suit: Suit) -> &'static str;
+
+    fn rank_name(&self, rank: Rank, case: GrammaticalCase) -> &'static str;
+
+    /// Short form of the rank used in compact notations, like `K` for king.
+    fn rank_index(&self, rank: Rank) -> &'static str;
+
+    fn joker_name(&self, color: JokerColor, case: GrammaticalCase) -> &'static str;
+
+    fn card_name(&self, card: Card, case: GrammaticalCase) -> String;
+
+    /// Returns the card written as the suit symbol followed by the localized rank index.
+    fn card_suit_rank(&self, card: Card) -> String {
+        match (card.suit(), card.rank()) {
+            (Some(suit), Some(rank)) => {
+                format!("{}{}", suit.unicode_black(), self.rank_index(rank))
+            }
+            _ => card.to_suit_rank().unwrap_or_else(|_| "??".to_string()),
+        }
+    }
+
+    /// Parses a suit from its localized name, falling back to [Suit::from_str].
+    fn parse_suit(&self, s: &str) -> Result<Suit, ParseCardError> {
+        let lower = s.trim().to_lowercase();
+        Suit::iter()
+            .copied()
+            .find(|&suit| lower == self.suit_name(suit))
+            .map_or_else(|| Suit::from_str(s), Ok)
+    }
+
+    /// Parses a rank from its localized name in any case or its index,
+    /// falling back to [Rank::from_str].
+    fn parse_rank(&self, s: &str) -> Result<Rank, ParseCardError> {
+        let lower = s.trim().to_lowercase();
+        Rank::iter_with_knight()
+            .copied()
+            .find(|&rank| {
+                lower == self.rank_index(rank).to_lowercase()
+                    || GrammaticalCase::iter().any(|&case| lower == self.rank_name(rank, case))
+            })
+            .map_or_else(|| Rank::from_str(s), Ok)
+    }
+
+    /// Parses a card from its localized name in any case or its localized suit-rank form,
+    /// falling back to [Card::from_str].
+    fn parse_card(&self, s: &str) -> Result<Card, ParseCardError> {
+        let lower = s.trim().to_lowercase();
+        TarotSet::ALL
+            .iter()
+            .find(|&card| {
+                lower == self.card_suit_rank(card).to_lowercase()
+                    || GrammaticalCase::iter().any(|&case| lower == self.card_name(card, case))
+            })
+            .map_or_else(|| Card::from_str(s), Ok)
+    }
+}
+
+pub struct English;
+
+impl Locale for English {
+    fn suit_name(&self, suit: Suit) -> &'static str {
+        suit.name()
+    }
+
+    fn rank_name(&self, rank: Rank, _case: GrammaticalCase) -> &'static str {
+        rank.name()
+    }
+
+    fn rank_index(&self, rank: Rank) -> &'static str {
+        rank.index()
+    }
+
+    fn joker_name(&self, color: JokerColor, _case: GrammaticalCase) -> &'static str {
+        match color {
+            JokerColor::Red => "red joker",
+            JokerColor::Black => "black joker",
+            JokerColor::White => "white joker",
+        }
+    }
+
+    fn card_name(&self, card: Card, _case: GrammaticalCase) -> String {
+        card.name().unwrap_or_else(|_| "??".to_string())
+    }
+}
+
+pub struct Polish;
+
+impl Locale for Polish {
+    fn suit_name(&self, suit: Suit) -> &'static str {
+        match suit {
+            Suit::Spades => "pik",
+            Suit::Hearts => "kier",
+            Suit::Diamonds => "karo",
+            Suit::Clubs => "trefl",
+        }
+    }
+
+    fn rank_name(&self, rank: Rank, case: GrammaticalCase) -> &'static str {
+        use GrammaticalCase::*;
+        match (rank, case) {
+            (Rank::Ace, Nominative) => "as",
+            (Rank::Ace, Genitive | Accusative) => "asa",
+            (Rank::Two, Nominative) => "dwójka",
+            (Rank::Two, Genitive) => "dwójki",
+            (Rank::Two, Accusative) => "dwójkę",
+            (Rank::Three, Nominative) => "trójka",
+            (Rank::Three, Genitive) => "trójki",
+            (Rank::Three, Accusative) => "trójkę",
+            (Rank::Four, Nominative) => "czwórka",
+            (Rank::Four, Genitive) => "czwórki",
+            (Rank::Four, Accusative) => "czwórkę",
+            (Rank::Five, Nominative) => "piątka",
+            (Rank::Five, Genitive) => "piątki",
+            (Rank::Five, Accusative) => "piątkę",
+            (Rank::Six, Nominative) => "szóstka",
+            (Rank::Six, Genitive) => "szóstki",
+            (Rank::Six, Accusative) => "szóstkę",
+            (Rank::Seven, Nominative) => "siódemka",
+            (Rank::Seven, Genitive) => "siódemki",
+            (Rank::Seven, Accusative) => "siódemkę",
+            (Rank::Eight, Nominative) => "ósemka",
+            (Rank::Eight, Genitive) => "ósemki",
+            (Rank::Eight, Accusative) => "ósemkę",
+            (Rank::Nine, Nominative) => "dziewiątka",
+            (Rank::Nine, Genitive) => "dziewiątki",
+            (Rank::Nine, Accusative) => "dziewiątkę",
+            (Rank::Ten, Nominative) => "dziesiątka",
+            (Rank::Ten, Genitive) => "dziesiątki",
+            (Rank::Ten, Accusative) => "dziesiątkę",
+            (Rank::Jack, Nominative) => "walet",
+            (Rank::Jack, Genitive | Accusative) => "waleta",
+            (Rank::Queen, Nominative) => "dama",
+            (Rank::Queen, Genitive) => "damy",
+            (Rank::Queen, Accusative) => "damę",
+            (Rank::King, Nominative) => "król",
+            (Rank::King, Genitive | Accusative) => "króla",
+            (Rank::Knight, Nominative) => "rycerz",
+            (Rank::Knight, Genitive | Accusative) => "rycerza",
+        }
+    }
+
+    fn rank_index(&self, rank: Rank) -> &'static str {
+        match rank {
+            Rank::Jack => "W",
+            Rank::Queen => "D",
+            Rank::King => "K",
+            Rank::Ace => "A",
+            _ => rank.index(),
+        }
+    }
+
+    fn joker_name(&self, color: JokerColor, case: GrammaticalCase) -> &'static str {
+        use GrammaticalCase::*;
+        match (color, case) {
+            (JokerColor::Red, Nominative) => "czerwony joker",
+            (JokerColor::Red, Genitive | Accusative) => "czerwonego jokera",
+            (JokerColor::Black, Nominative) => "czarny joker",
+            (JokerColor::Black, Genitive | Accusative) => "czarnego jokera",
+            (JokerColor::White, Nominative) => "biały joker",
+            (JokerColor::White, Genitive | Accusative) => "białego jokera",
+        }
+    }
+
+    /// The suit follows the rank without being declined, like "damę kier".
+    fn card_name(&self, card: Card, case: GrammaticalCase) -> String {
+        match (card.suit(), card.rank(), card.joker_color()) {
+            (Some(suit), Some(rank), _) => {
+                format!("{} {}", self.rank_name(rank, case), self.suit_name(suit))
+            }
+            (_, _, Some(color)) => self.joker_name(color, case).to_string(),
+            _ if card.is_fool() => match case {
+                GrammaticalCase::Nominative => "błazen".to_string(),
+                GrammaticalCase::Genitive | GrammaticalCase::Accusative => "błazna".to_string(),
+            },
+            _ => match (card.trump_number(), case) {
+                (Some(number), GrammaticalCase::Genitive) => format!("atutu {}", number),
+                (Some(number), _) => format!("atut {}", number),
+                (None, _) => "??".to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polish() {
+        let queen = Card::new(Suit::Hearts, Rank::Queen);
+        assert_eq!(
+            Polish.card_name(queen, GrammaticalCase::Nominative),
+            "dama kier"
+        );
+        assert_eq!(
+            Polish.card_name(queen, GrammaticalCase::Accusative),
+            "damę kier"
+        );
+        assert_eq!(Polish.card_suit_rank(queen), "♥D");
+        assert_eq!(Polish.parse_card("damę kier"), Ok(queen));
+        assert_eq!(Polish.parse_card("♥D"), Ok(queen));
+        assert_eq!(Polish.parse_card("Qh"), Ok(queen));
+        assert_eq!(
+            Polish.parse_card("czarnego jokera"),
+            Ok(Card::new_joker(JokerColor::Black))
+        );
+        assert_eq!(Polish.parse_suit("Trefl"), Ok(Suit::Clubs));
+        assert_eq!(Polish.parse_rank("w"), Ok(Rank::Jack));
+
+        let knight = Card::new(Suit::Spades, Rank::Knight);
+        assert_eq!(
+            Polish.card_name(knight, GrammaticalCase::Accusative),
+            "rycerza pik"
+        );
+        assert_eq!(Polish.parse_card("rycerz pik"), Ok(knight));
+        let trump = Card::new_trump(21).unwrap();
+        assert_eq!(
+            Polish.card_name(trump, GrammaticalCase::Genitive),
+            "atutu 21"
+        );
+        assert_eq!(Polish.parse_card("atut 21"), Ok(trump));
+        assert_eq!(Polish.parse_card("błazna"), Ok(Card::FOOL));
+    }
+
+    #[test]
+    fn english() {
+        let king = Card::new(Suit::Diamonds, Rank::King);
+        assert_eq!(
+            English.card_name(king, GrammaticalCase::Accusative),
+            "king of diamonds"
+        );
+        assert_eq!(English.parse_card("King of diamonds"), Ok(king));
+        assert_eq!(English.parse_card("♦K"), Ok(king));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::cards::Card;
+use std::fmt;
+use std::fmt::Display;
+
+/// The ways a card can be written.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Notation {
+    /// Like `Kd`, see [Card::to_ascii].
+    Ascii,
+    /// Like `♦K`, see [Card::to_suit_rank].
+    #[default]
+    SuitRank,
+    /// Like `🃎`, see [Card::to_unicode].
+    Unicode,
+    /// Like `king of diamonds`, see [Card::name].
+    Name,
+}
+
+impl Notation {
+    /// Returns the notation selected by the format spec:
+    /// [Notation::Name] with the alternate flag (`{:#}`), [Notation::SuitRank] otherwise.
+    pub fn from_formatter(f: &fmt::Formatter) -> Notation {
+        if f.alternate() {
+            Notation::Name
+        } else {
+            Notation::SuitRank
+        }
+    }
+}
+
+/// Something made of cards that can be displayed in any [Notation].
+pub trait NotationDisplay {
+    fn fmt_notation(&self, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result;
+
+    /// Returns a wrapper displaying this in the given notation.
+    fn display(&self, notation: Notation) -> Notated<'_, Self> {
+        Notated {
+            value: self,
+            notation,
+        }
+    }
+}
+
+pub struct Notated<'a, T: ?Sized> {
+    value: &'a T,
+    notation: Notation,
+}
+
+impl<T: NotationDisplay + ?Sized> Display for Notated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_notation(f, self.notation)
+    }
+}
+
+impl NotationDisplay for Card {
+    /// Writes the card padded according to the format spec, or `??` if it can't be written.
+    fn fmt_notation(&self, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result {
+        let text = match notation {
+            Notation::Ascii => self.to_ascii(),
+            Notation::SuitRank => self.to_suit_rank(),
+            Notation::Unicode => self.to_unicode().map(String::from),
+            Notation::Name => self.name(),
+        };
+        f.pad(text.as_deref().unwrap_or("??"))
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_notation(f, Notation::from_formatter(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::hand::{Hand, SortedCard};
+    use crate::cards::pile::Pile;
+    use crate::cards::{JokerColor, Rank, Suit};
+
+    #[test]
+    fn card() {
+        let card = Card::new(Suit::Diamonds, Rank::King);
+        assert_eq!(format!("{}", card), "♦K");
+        assert_eq!(format!("{:#}", card), "king of diamonds");
+        assert_eq!(format!("{:>4}", card), "  ♦K");
+        assert_eq!(card.display(Notation::Ascii).to_string(), "Kd");
+        assert_eq!(card.display(Notation::Unicode).to_string(), "🃎");
+        assert_eq!(
+            Card::new_joker(JokerColor::Red)
+                .display(Notation::Name)
+                .to_string(),
+            "red joker"
+        );
+    }
+
+    #[test]
+    fn hand_and_pile() {
+        let mut hand: Hand<SortedCard> = Hand::new();
+        hand.add_card(Card::new(Suit::Hearts, Rank::Ten));
+        hand.add_card(Card::new(Suit::Spades, Rank::Ace));
+        assert_eq!(hand.to_string(), "♠A, ♥10");
+        assert_eq!(hand.display(Notation::Ascii).to_string(), "As, 10h");
+        assert_eq!(format!("{:#}", hand), "ace of spades, ten of hearts");
+        assert_eq!(format!("{:>10}|", hand), "   ♠A, ♥10|");
+        assert_eq!(format!("{:-<9}|", hand), "♠A, ♥10--|");
+
+        let mut pile = Pile::new_empty();
+        pile.add_on_top(Card::new(Suit::Clubs, Rank::Two));
+        assert_eq!(
+            pile.display(Notation::Ascii).to_string(),
+            "Pile with 1 cards, 2c on top"
+        );
+        assert_eq!(format!("{:>30}", pile), "  Pile with 1 cards, ♣2 on top");
+    }
+}
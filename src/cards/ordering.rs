@@ -0,0 +1,170 @@
+use crate::cards::{Card, Rank, Suit};
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+/// A card order chosen at runtime, e.g. to let a player re-sort their hand in the UI
+/// without changing the type of the [Hand](crate::cards::hand::Hand).
+///
+/// Start from [CardOrdering::standard], which matches [SortedCard](crate::cards::hand::SortedCard),
+/// and adjust it with the builder methods. Jokers always come after the suited cards, in the order
+/// red, black, white, followed by the Fool and the trumps.
+#[derive(Clone)]
+pub struct CardOrdering {
+    suit_order: [Suit; 4],
+    ace_high: bool,
+    rank_first: bool,
+    group: Option<Rc<dyn Fn(Card) -> u8>>,
+}
+
+impl fmt::Debug for CardOrdering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CardOrdering")
+            .field("suit_order", &self.suit_order)
+            .field("ace_high", &self.ace_high)
+            .field("rank_first", &self.rank_first)
+            .field("grouped", &self.group.is_some())
+            .finish()
+    }
+}
+
+impl Default for CardOrdering {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl CardOrdering {
+    /// Suits in the order spades, hearts, diamonds, clubs, aces low within each suit.
+    pub fn standard() -> Self {
+        CardOrdering {
+            suit_order: [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs],
+            ace_high: false,
+            rank_first: false,
+            group: None,
+        }
+    }
+
+    /// Orders by rank first, and by suit within the same rank.
+    pub fn rank_first(mut self) -> Self {
+        self.rank_first = true;
+        self
+    }
+
+    /// Puts aces above kings.
+    pub fn ace_high(mut self) -> Self {
+        self.ace_high = true;
+        self
+    }
+
+    pub fn suit_order(mut self, suit_order: [Suit; 4]) -> Self {
+        self.suit_order = suit_order;
+        self
+    }
+
+    /// Alternates black and red suits: spades, hearts, clubs, diamonds.
+    pub fn alternating_colors(self) -> Self {
+        self.suit_order([Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds])
+    }
+
+    /// Orders the cards by the group key first, lowest first, and by the rest of the ordering
+    /// within each group.
+    pub fn grouped_by<F>(mut self, key: F) -> Self
+    where
+        F: Fn(Card) -> u8 + 'static,
+    {
+        self.group = Some(Rc::new(key));
+        self
+    }
+
+    fn key(&self, card: Card) -> (u8, u8, u8, u8) {
+        let group = self.group.as_ref().map_or(0, |key| key(card));
+        if let Some(color) = card.joker_color() {
+            return (group, 1, color as u8, 0);
+        }
+        if !card.is_suited() {
+            return (group, 2, card.trump_number().unwrap_or(0), 0);
+        }
+
+        let suit = card.suit().unwrap();
+        let suit = self.suit_order.iter().position(|&s| s == suit).unwrap() as u8;
+        let rank = match card.rank().unwrap() {
+            Rank::Ace if self.ace_high => Rank::King.position() as u8 + 1,
+            rank => rank.position() as u8,
+        };
+        if self.rank_first {
+            (group, 0, rank, suit)
+        } else {
+            (group, 0, suit, rank)
+        }
+    }
+
+    pub fn compare(&self, a: Card, b: Card) -> Ordering {
+        self.key(a).cmp(&self.key(b))
+    }
+
+    pub fn sort(&self, cards: &mut [Card]) {
+        cards.sort_by_cached_key(|&card| self.key(card));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::{generate_deck, DeckSpec};
+    use crate::cards::hand::SortedCard;
+    use crate::cards::parse::parse_cards;
+
+    fn sorted(ordering: &CardOrdering, cards: &str) -> Vec<Card> {
+        let mut cards = parse_cards(cards).unwrap();
+        ordering.sort(&mut cards);
+        cards
+    }
+
+    #[test]
+    fn standard_matches_sorted_card() {
+        let mut cards = generate_deck(3).unwrap();
+        cards.reverse();
+        let mut expected = cards.clone();
+        expected.sort_by_key(|&card| SortedCard::from(card));
+        CardOrdering::standard().sort(&mut cards);
+        assert_eq!(cards, expected);
+
+        let mut tarot = DeckSpec::tarot().generate().unwrap();
+        tarot.reverse();
+        let mut expected = tarot.clone();
+        expected.sort_by_key(|&card| SortedCard::from(card));
+        CardOrdering::standard().sort(&mut tarot);
+        assert_eq!(tarot, expected);
+        assert_eq!(
+            &tarot[10..14],
+            parse_cards("Js Cs Qs Ks").unwrap().as_slice()
+        );
+        assert_eq!(tarot[56], Card::FOOL);
+    }
+
+    #[test]
+    fn orderings() {
+        let cards = "J1 Kd As 2s Ah Qc";
+        assert_eq!(
+            sorted(&CardOrdering::standard().rank_first(), cards),
+            parse_cards("As Ah 2s Qc Kd J1").unwrap()
+        );
+        assert_eq!(
+            sorted(&CardOrdering::standard().ace_high(), cards),
+            parse_cards("2s As Ah Kd Qc J1").unwrap()
+        );
+        assert_eq!(
+            sorted(&CardOrdering::standard().alternating_colors(), cards),
+            parse_cards("As 2s Ah Qc Kd J1").unwrap()
+        );
+        let high_cards_last = CardOrdering::standard().grouped_by(|card| {
+            card.rank()
+                .map_or(2, |rank| (rank >= Rank::Queen || rank == Rank::Ace) as u8)
+        });
+        assert_eq!(
+            sorted(&high_cards_last, cards),
+            parse_cards("2s As Ah Kd Qc J1").unwrap()
+        );
+    }
+}
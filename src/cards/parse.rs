@@ -0,0 +1,225 @@
+use crate::cards::{Card, JokerColor, Rank, Suit};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseCardError {
+    UnknownSuit(String),
+    UnknownRank(String),
+    UnknownCard(String),
+}
+
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::UnknownSuit(s) => write!(f, "`{}` is not a suit.", s),
+            ParseCardError::UnknownRank(s) => write!(f, "`{}` is not a rank.", s),
+            ParseCardError::UnknownCard(s) => write!(f, "`{}` is not a card.", s),
+        }
+    }
+}
+
+impl Error for ParseCardError {}
+
+/// Parses a suit from its name, letter, or black or white symbol, ignoring case.
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Suit::iter()
+            .copied()
+            .find(|suit| {
+                s == suit.name()
+                    || s == suit.letter().to_string()
+                    || s == suit.unicode_black().to_string()
+                    || s == suit.unicode_white().to_string()
+            })
+            .ok_or(ParseCardError::UnknownSuit(s))
+    }
+}
+
+/// Parses a rank from its name or index, ignoring case. Ten can also be written as `T`,
+/// and the knight's index is `C`, as in the French *cavalier*.
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        if s == "t" {
+            return Ok(Rank::Ten);
+        }
+        Rank::iter_with_knight()
+            .copied()
+            .find(|rank| s == rank.name() || s == rank.index().to_lowercase())
+            .ok_or(ParseCardError::UnknownRank(s))
+    }
+}
+
+impl JokerColor {
+    fn from_index(s: &str) -> Option<JokerColor> {
+        JokerColor::try_from(s.parse::<u8>().ok()?).ok()
+    }
+}
+
+/// Returns the trump with the number, or the Fool for 0.
+fn trump_from_index(s: &str) -> Option<Card> {
+    match s.trim().parse::<u8>().ok()? {
+        0 => Some(Card::FOOL),
+        number => Card::new_trump(number).ok(),
+    }
+}
+
+/// Parses a card from any of the notations it can be printed in, ignoring case:
+/// - ASCII, like `Kd`, `10h`, `Th` or `Cd` for the knight, `J1` to `J3` for jokers,
+///   and `T1` to `T21` for trumps, with `T0` for the Fool,
+/// - suit and rank, like `♦K`, and `🃏1` to `🃏3` for jokers,
+/// - a Unicode playing card, like `🃎`,
+/// - a name, like `king of diamonds`, `red joker`, `trump 21` or `fool`.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let unknown = || ParseCardError::UnknownCard(s.to_string());
+
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Card::from_unicode(c).ok_or_else(unknown);
+        }
+
+        let lower = s.to_lowercase();
+        if let Some(index) = lower.strip_prefix('j').or(lower.strip_prefix('🃏')) {
+            if let Some(color) = JokerColor::from_index(index) {
+                return Ok(Card::new_joker(color));
+            }
+        }
+        if let Some(card) = lower
+            .strip_prefix("trump ")
+            .or(lower.strip_prefix('t'))
+            .and_then(trump_from_index)
+        {
+            return Ok(card);
+        }
+        if lower == "fool" {
+            return Ok(Card::FOOL);
+        }
+        if let Some(name) = lower.strip_suffix(" joker") {
+            return JokerColor::iter()
+                .copied()
+                .find(|color| color.name() == name)
+                .map(Card::new_joker)
+                .ok_or_else(unknown);
+        }
+
+        if let Some((rank, suit)) = lower.split_once(" of ") {
+            return Ok(Card::new(suit.parse()?, rank.parse()?));
+        }
+
+        let first = lower.chars().next().ok_or_else(unknown)?;
+        if let Ok(suit) = Suit::from_str(&first.to_string()) {
+            if !suit.letter().eq_ignore_ascii_case(&first) {
+                return Ok(Card::new(suit, lower[first.len_utf8()..].parse()?));
+            }
+        }
+
+        let last = lower.chars().next_back().ok_or_else(unknown)?;
+        let rank = &lower[..lower.len() - last.len_utf8()];
+        Ok(Card::new(last.to_string().parse()?, rank.parse()?))
+    }
+}
+
+/// Parses a list of cards separated by whitespace or commas.
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, ParseCardError> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(Card::from_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::set::TarotSet;
+
+    #[test]
+    fn parse() {
+        let king = Card::new(Suit::Diamonds, Rank::King);
+        assert_eq!("Kd".parse(), Ok(king));
+        assert_eq!("kD".parse(), Ok(king));
+        assert_eq!("♦K".parse(), Ok(king));
+        assert_eq!("🃎".parse(), Ok(king));
+        assert_eq!("King of Diamonds".parse(), Ok(king));
+
+        let ten = Card::new(Suit::Hearts, Rank::Ten);
+        assert_eq!("10h".parse(), Ok(ten));
+        assert_eq!("Th".parse(), Ok(ten));
+        assert_eq!("♡10".parse(), Ok(ten));
+        assert_eq!("Jh".parse(), Ok(Card::new(Suit::Hearts, Rank::Jack)));
+
+        let joker = Card::new_joker(JokerColor::Black);
+        assert_eq!("J2".parse(), Ok(joker));
+        assert_eq!("🃏2".parse(), Ok(joker));
+        assert_eq!("🃏".parse(), Ok(joker));
+        assert_eq!("black joker".parse(), Ok(joker));
+
+        assert_eq!(
+            "Xd".parse::<Card>(),
+            Err(ParseCardError::UnknownRank("x".to_string()))
+        );
+        assert_eq!(
+            "J4".parse::<Card>(),
+            Err(ParseCardError::UnknownSuit("4".to_string()))
+        );
+        assert_eq!(
+            "🂠".parse::<Card>(),
+            Err(ParseCardError::UnknownCard("🂠".to_string()))
+        );
+        assert_eq!(
+            "T22".parse::<Card>(),
+            Err(ParseCardError::UnknownSuit("2".to_string()))
+        );
+    }
+
+    #[test]
+    fn tarot() {
+        let knight = Card::new(Suit::Clubs, Rank::Knight);
+        assert_eq!("Cc".parse(), Ok(knight));
+        assert_eq!("♣C".parse(), Ok(knight));
+        assert_eq!("🃜".parse(), Ok(knight));
+        assert_eq!("Knight of Clubs".parse(), Ok(knight));
+
+        let trump = Card::new_trump(1).unwrap();
+        assert_eq!("T1".parse(), Ok(trump));
+        assert_eq!("🃡".parse(), Ok(trump));
+        assert_eq!("Trump 1".parse(), Ok(trump));
+        assert_eq!("T0".parse(), Ok(Card::FOOL));
+        assert_eq!("Fool".parse(), Ok(Card::FOOL));
+        assert_eq!("Th".parse(), Ok(Card::new(Suit::Hearts, Rank::Ten)));
+    }
+
+    #[test]
+    fn round_trip() {
+        for card in TarotSet::ALL {
+            assert_eq!(card.to_ascii().unwrap().parse(), Ok(card));
+            assert_eq!(card.to_suit_rank().unwrap().parse(), Ok(card));
+            assert_eq!(card.to_unicode().unwrap().to_string().parse(), Ok(card));
+            assert_eq!(card.name().unwrap().parse(), Ok(card));
+        }
+    }
+
+    #[test]
+    fn list() {
+        assert_eq!(
+            parse_cards("Ks, 2h  J1,"),
+            Ok(vec![
+                Card::new(Suit::Spades, Rank::King),
+                Card::new(Suit::Hearts, Rank::Two),
+                Card::new_joker(JokerColor::Red),
+            ])
+        );
+        assert!(parse_cards("Ks, nope").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::cards::hand::SortedCard;
+use crate::cards::notation::{Notation, NotationDisplay};
+use crate::cards::set::CardCounts;
+use crate::cards::Card;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A card together with which copy of its face it is, so identical cards from several decks,
+/// or more than three jokers, can be told apart.
+///
+/// Two physical cards are equal only if both the face and the copy match. Game rules should
+/// compare faces, using [PhysicalCard::card] or [PhysicalCard::same_face].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PhysicalCard {
+    card: Card,
+    copy: u16,
+}
+
+impl PhysicalCard {
+    pub fn new(card: Card, copy: u16) -> Self {
+        PhysicalCard { card, copy }
+    }
+
+    /// The face of the card.
+    pub fn card(&self) -> Card {
+        self.card
+    }
+
+    /// Which copy of the face this is, counting from 0.
+    pub fn copy(&self) -> u16 {
+        self.copy
+    }
+
+    pub fn same_face(&self, other: &PhysicalCard) -> bool {
+        self.card == other.card
+    }
+
+    /// Numbers the copies of each face in the order they appear.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a face appears more than `u16::MAX` times, as the copies couldn't be told
+    /// apart anymore.
+    pub fn tag_all<I: IntoIterator<Item = Card>>(cards: I) -> Vec<PhysicalCard> {
+        let mut seen = CardCounts::new();
+        cards
+            .into_iter()
+            .map(|card| {
+                let copy = seen.count(card) as u16;
+                seen.insert(card);
+                PhysicalCard::new(card, copy)
+            })
+            .collect()
+    }
+}
+
+impl From<PhysicalCard> for Card {
+    fn from(card: PhysicalCard) -> Self {
+        card.card
+    }
+}
+
+impl PartialEq<Card> for PhysicalCard {
+    fn eq(&self, other: &Card) -> bool {
+        self.card == *other
+    }
+}
+
+/// Orders by face like [SortedCard], then by copy.
+impl Ord for PhysicalCard {
+    fn cmp(&self, other: &Self) -> Ordering {
+        SortedCard::from(self.card)
+            .cmp(&SortedCard::from(other.card))
+            .then(self.copy.cmp(&other.copy))
+    }
+}
+
+impl PartialOrd for PhysicalCard {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl NotationDisplay for PhysicalCard {
+    /// Writes only the face; the copy doesn't show on the card.
+    fn fmt_notation(&self, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result {
+        self.card.fmt_notation(f, notation)
+    }
+}
+
+impl fmt::Display for PhysicalCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_notation(f, Notation::from_formatter(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::DeckSpec;
+    use crate::cards::hand::Hand;
+    use crate::cards::pile::Pile;
+    use crate::cards::zone::{move_cards, Take, Zone, ZoneError};
+    use crate::cards::{JokerColor, Rank, Suit};
+
+    #[test]
+    fn identity() {
+        let deck = DeckSpec::standard().copies(2).jokers(5);
+        let cards = deck.generate_physical();
+        assert_eq!(cards.len(), 114);
+        let king = Card::new(Suit::Hearts, Rank::King);
+        let kings: Vec<&PhysicalCard> = cards.iter().filter(|card| **card == king).collect();
+        assert_eq!(kings.len(), 2);
+        assert_ne!(kings[0], kings[1]);
+        assert!(kings[0].same_face(kings[1]));
+        assert_eq!((kings[0].copy(), kings[1].copy()), (0, 1));
+
+        let red_jokers = cards
+            .iter()
+            .filter(|card| card.card() == Card::new_joker(JokerColor::Red))
+            .count();
+        assert_eq!(red_jokers, 4);
+
+        let mut hand: Hand<PhysicalCard> = Hand::new();
+        hand.add_card(*kings[1]);
+        assert_eq!(hand.to_string(), "♥K");
+        assert_eq!(format!("{}", kings[1]), "♥K");
+        assert!(!hand.remove_card(*kings[0]));
+        assert_eq!(hand.remove_face(king), Some(*kings[1]));
+    }
+
+    #[test]
+    fn zones() {
+        let mut pile = Pile::new_empty();
+        for card in DeckSpec::standard().copies(2).generate_physical() {
+            pile.add_on_top(card);
+        }
+        let top = pile.peek_n(1)[0];
+        let twin = PhysicalCard::new(top.card(), 0);
+        assert_eq!(top.copy(), 1);
+
+        let mut hand: Hand<PhysicalCard> = Hand::new();
+        let mut table = Vec::new();
+        move_cards(&mut pile, &mut hand, Take::Top(3)).unwrap();
+        assert_eq!(
+            move_cards(&mut hand, &mut table, Take::Cards(&[twin])),
+            Err(ZoneError::CardMissing(top.card()))
+        );
+        move_cards(&mut hand, &mut table, Take::Cards(&[top])).unwrap();
+        move_cards(&mut pile, &mut hand, Take::Cards(&[twin])).unwrap();
+        assert_eq!(table, vec![top]);
+        assert!(hand.cards().contains(&twin));
+        assert_eq!(hand.count(top.card()), 1);
+        assert_eq!(pile.card_counts().count(top.card()), 0);
+    }
+}
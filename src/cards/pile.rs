@@ -1,18 +1,37 @@
 use crate::cards::hand::HasHand;
+use crate::cards::notation::{Notation, NotationDisplay};
+use crate::cards::set::CardCounts;
+use crate::cards::shuffle::ShuffleModel;
+use crate::cards::zone::{positions_of, Take, Zone, ZoneError, WRONG_SNAPSHOT};
 use crate::cards::Card;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::any::Any;
+use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
 
-pub struct Pile {
-    cards: Vec<Card>,
+/// A face-down pile of cards, like the draw pile.
+///
+/// Only the accessible part can be drawn from in order; the rest is reshuffled in when it
+/// runs out. Piles hold [Card]s by default, or e.g.
+/// [PhysicalCard](crate::cards::physical::PhysicalCard)s to keep track of every copy.
+#[derive(Clone)]
+pub struct Pile<C = Card> {
+    cards: Vec<C>,
     accessible: usize,
+    shuffle_model: ShuffleModel,
+    /// Seeded generator for all shuffles, or `None` to use the thread's.
+    rng: Option<StdRng>,
 }
 
-impl Pile {
+impl<C: Copy> Pile<C> {
     pub fn new_empty() -> Self {
         Pile {
             cards: Vec::new(),
             accessible: 0,
+            shuffle_model: ShuffleModel::Uniform,
+            rng: None,
         }
     }
 
@@ -20,67 +39,191 @@ impl Pile {
         Pile {
             cards: Vec::with_capacity(capacity),
             accessible: 0,
+            shuffle_model: ShuffleModel::Uniform,
+            rng: None,
         }
     }
 
-    pub fn of(cards: Vec<Card>) -> Self {
+    pub fn of(cards: Vec<C>) -> Self {
         Pile {
             cards,
             accessible: 0,
+            shuffle_model: ShuffleModel::Uniform,
+            rng: None,
         }
     }
 
-    pub fn add_card(&mut self, card: Card) {
+    /// Sets how the pile gets shuffled from now on.
+    pub fn set_shuffle_model(&mut self, model: ShuffleModel) {
+        self.shuffle_model = model;
+    }
+
+    pub fn shuffle_model(&self) -> ShuffleModel {
+        self.shuffle_model
+    }
+
+    /// Makes every following shuffle use a generator seeded with `seed`, including the
+    /// reshuffles when the accessible part runs out, so the pile always deals the same cards.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    pub fn add_card(&mut self, card: C) {
         self.cards.push(card);
     }
 
-    pub fn add_cards(&mut self, cards: Vec<Card>) {
+    pub fn add_cards(&mut self, cards: Vec<C>) {
         self.cards.extend(cards);
     }
 
-    pub fn add_on_top(&mut self, card: Card) {
+    pub fn add_on_top(&mut self, card: C) {
         self.cards.push(card);
         let last = self.cards.len() - 1;
         self.cards.swap(self.accessible, last);
         self.accessible += 1;
     }
 
-    pub fn pop(&mut self) -> Option<Card> {
+    pub fn pop(&mut self) -> Option<C> {
         if self.cards.is_empty() {
             return None;
         }
-        if self.accessible <= 0 {
+        if self.accessible == 0 {
             self.shuffle();
         }
         self.accessible -= 1;
         Some(self.cards.swap_remove(self.accessible))
     }
 
-    pub fn seek(&mut self) -> Option<Card> {
+    pub fn seek(&mut self) -> Option<C> {
         if self.cards.is_empty() {
             return None;
         }
-        if self.accessible <= 0 {
+        if self.accessible == 0 {
             self.shuffle();
         }
         Some(self.cards[self.accessible - 1])
     }
 
-    pub fn try_seek(&self) -> Option<Card> {
+    pub fn try_seek(&self) -> Option<C> {
         if self.cards.is_empty() {
             return None;
         }
-        if self.accessible <= 0 {
+        if self.accessible == 0 {
             return None;
         }
         Some(self.cards[self.accessible - 1])
     }
 
+    /// Returns up to `n` accessible cards from the top down, without reshuffling.
+    pub fn peek_n(&self, n: usize) -> Vec<C> {
+        self.iter_accessible().take(n).collect()
+    }
+
+    /// Pops up to `n` cards, top first, reshuffling like [Pile::pop] when needed.
+    /// Returns fewer cards if the whole pile runs out.
+    pub fn pop_n(&mut self, n: usize) -> Vec<C> {
+        (0..n).map_while(|_| self.pop()).collect()
+    }
+
+    /// Cuts the accessible part: the top `at` cards are put under the rest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than [Pile::count_accessible].
+    pub fn cut(&mut self, at: usize) {
+        assert!(
+            at <= self.accessible,
+            "can't cut below the accessible cards"
+        );
+        self.cards[..self.accessible].rotate_right(at);
+    }
+
+    /// Inserts the card into the accessible part, `pos` cards from the top.
+    /// `0` puts it on top and [Pile::count_accessible] at the bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is greater than [Pile::count_accessible].
+    pub fn insert_at(&mut self, pos: usize, card: C) {
+        assert!(
+            pos <= self.accessible,
+            "can't insert below the accessible cards"
+        );
+        self.cards.insert(self.accessible - pos, card);
+        self.accessible += 1;
+    }
+
+    /// Returns the bottom accessible card, without reshuffling.
+    pub fn bottom(&self) -> Option<C> {
+        self.cards[..self.accessible].first().copied()
+    }
+
+    /// Removes the bottom accessible card, reshuffling like [Pile::pop] when needed.
+    pub fn pop_bottom(&mut self) -> Option<C> {
+        if self.cards.is_empty() {
+            return None;
+        }
+        if self.accessible == 0 {
+            self.shuffle();
+        }
+        self.accessible -= 1;
+        Some(self.cards.remove(0))
+    }
+
+    /// Moves up to `n` cards from the top into the non-accessible part without revealing them,
+    /// returning how many were burned.
+    pub fn burn(&mut self, n: usize) -> usize {
+        let burned = n.min(self.accessible);
+        self.accessible -= burned;
+        burned
+    }
+
+    /// Iterates over the accessible cards from the top down.
+    pub fn iter_accessible(&self) -> impl Iterator<Item = C> + '_ {
+        self.cards[..self.accessible].iter().rev().copied()
+    }
+
+    /// Iterates over the non-accessible cards, in no particular order.
+    pub fn iter_inaccessible(&self) -> impl Iterator<Item = C> + '_ {
+        self.cards[self.accessible..].iter().copied()
+    }
+
+    /// Adds the cards to the accessible part of the pile and shuffles that part,
+    /// leaving the non-accessible cards untouched.
+    pub fn shuffle_in(&mut self, cards: Vec<C>) {
+        for card in cards {
+            self.add_on_top(card);
+        }
+        self.shuffle_accessible();
+    }
+
+    /// Shuffles only the accessible part, leaving the non-accessible cards untouched.
+    pub fn shuffle_accessible(&mut self) {
+        let accessible = &mut self.cards[..self.accessible];
+        match &mut self.rng {
+            Some(rng) => self.shuffle_model.shuffle(accessible, rng),
+            None => self
+                .shuffle_model
+                .shuffle(accessible, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Shuffles the whole pile with its [ShuffleModel], making every card accessible.
+    /// Uses the generator from [Pile::set_seed] if there is one.
     pub fn shuffle(&mut self) {
-        use rand::seq::SliceRandom;
-        use rand::thread_rng;
+        match self.rng.take() {
+            Some(mut rng) => {
+                self.shuffle_with(&mut rng);
+                self.rng = Some(rng);
+            }
+            None => self.shuffle_with(&mut rand::thread_rng()),
+        }
+    }
 
-        self.cards.shuffle(&mut thread_rng());
+    /// Shuffles like [Pile::shuffle] with the given random number generator,
+    /// e.g. a seeded one to get the same order every time.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.shuffle_model.shuffle(&mut self.cards, rng);
 
         self.accessible = self.cards.len();
     }
@@ -97,47 +240,150 @@ impl Pile {
         self.cards.len()
     }
 
-    pub fn deal_to<C: Ord + From<Card> + Into<Card> + Clone>(
+    pub fn deal_to<T: Ord + From<C> + Into<Card> + Clone>(
         &mut self,
-        other: &mut dyn HasHand<CardType = C>,
+        other: &mut dyn HasHand<CardType = T>,
     ) -> Result<(), PileEmptyError> {
         if let Some(card) = self.pop() {
-            other.deal(card);
+            other.hand_mut().add_card(card);
             Ok(())
         } else {
-            Err(PileEmptyError)
+            Err(PileEmptyError { dealt: 0 })
         }
     }
+
+    /// Deals `cards_each` cards to every hand one at a time, going around the table
+    /// starting from the hand at `first_seat`.
+    pub fn deal_round_robin<H: HasHand>(
+        &mut self,
+        hands: &mut [H],
+        cards_each: usize,
+        first_seat: usize,
+    ) -> Result<(), PileEmptyError>
+    where
+        H::CardType: From<C>,
+    {
+        self.deal_packets(hands, &vec![1; cards_each], first_seat)
+    }
+
+    /// Deals the cards in packets going around the table starting from the hand at
+    /// `first_seat`: every hand gets a packet of `packets[0]` cards, then of `packets[1]`
+    /// cards, and so on. E.g. `[3, 2]` deals three cards to everyone, then two more.
+    ///
+    /// If the pile runs out, the error tells how many cards were dealt in total.
+    pub fn deal_packets<H: HasHand>(
+        &mut self,
+        hands: &mut [H],
+        packets: &[usize],
+        first_seat: usize,
+    ) -> Result<(), PileEmptyError>
+    where
+        H::CardType: From<C>,
+    {
+        let mut dealt = 0;
+        for &packet in packets {
+            for offset in 0..hands.len() {
+                let hand = &mut hands[(first_seat + offset) % hands.len()];
+                for _ in 0..packet {
+                    let card = self.pop().ok_or(PileEmptyError { dealt })?;
+                    hand.deal(card);
+                    dealt += 1;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-impl Display for Pile {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+/// Takes from the top of the accessible part, reshuffling like [Pile::pop] if it runs out.
+/// Specific cards can be taken from anywhere in the pile.
+impl<C: Copy + Eq + Into<Card> + 'static> Zone<C> for Pile<C> {
+    fn take(&mut self, take: Take<C>) -> Result<Vec<C>, ZoneError> {
+        match take {
+            Take::Top(n) => {
+                if n > self.cards.len() {
+                    return Err(ZoneError::NotEnoughCards {
+                        requested: n,
+                        available: self.cards.len(),
+                    });
+                }
+                Ok(self.pop_n(n))
+            }
+            Take::Cards(cards) => {
+                for position in positions_of(&self.cards, cards)? {
+                    if position < self.accessible {
+                        self.cards.remove(position);
+                        self.accessible -= 1;
+                    } else {
+                        self.cards.swap_remove(position);
+                    }
+                }
+                Ok(cards.to_vec())
+            }
+        }
+    }
+
+    fn put(&mut self, cards: Vec<C>) {
+        for card in cards.into_iter().rev() {
+            self.add_on_top(card);
+        }
+    }
+
+    fn card_counts(&self) -> CardCounts {
+        self.cards.iter().map(|&card| card.into()).collect()
+    }
+
+    fn snapshot(&self) -> Box<dyn Any> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Box<dyn Any>) {
+        *self = *snapshot.downcast().expect(WRONG_SNAPSHOT);
+    }
+}
+
+impl<C: Copy + NotationDisplay> NotationDisplay for Pile<C> {
+    /// Writes the size and top card of the pile, padded according to the format spec.
+    fn fmt_notation(&self, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result {
         if self.cards.is_empty() {
-            return write!(f, "Empty pile");
+            return f.pad("Empty pile");
         }
-        write!(
-            f,
+        let top = match self.try_seek() {
+            Some(card) => card.display(notation).to_string(),
+            None => "unknown".to_string(),
+        };
+        f.pad(&format!(
             "Pile with {} cards, {} on top",
             self.cards.len(),
-            self.try_seek()
-                .map(|c| c.to_suit_rank().unwrap())
-                .unwrap_or_else(|| "unknown".to_string())
-        )
+            top
+        ))
+    }
+}
+
+impl<C: Copy + NotationDisplay> Display for Pile<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_notation(f, Notation::from_formatter(f))
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PileEmptyError;
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PileEmptyError {
+    /// Number of cards dealt before the pile ran out.
+    pub dealt: usize,
+}
 
 impl Display for PileEmptyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Pile is empty.")
+        write!(f, "Pile is empty after dealing {} cards.", self.dealt)
     }
 }
 
+impl Error for PileEmptyError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cards::hand::{Hand, SortedCard};
     use crate::cards::{Rank, Suit};
 
     #[allow(unused_variables)]
@@ -176,4 +422,146 @@ mod tests {
         assert_eq!(pile.count_accessible(), 4);
         assert_eq!(pile.seek(), Some(f));
     }
+
+    #[test]
+    fn seeded_test() {
+        let reshuffled = || {
+            let mut pile = Pile::of(spades(&[Rank::Ace, Rank::Two, Rank::Three, Rank::Four]));
+            pile.set_seed(7);
+            let mut popped = pile.pop_n(4);
+            pile.add_cards(popped.clone());
+            popped.extend(pile.pop_n(4));
+            popped
+        };
+        assert_eq!(reshuffled(), reshuffled());
+    }
+
+    #[test]
+    fn shuffle_in_test() {
+        let a = Card::new(Suit::Hearts, Rank::Ace);
+        let b = Card::new(Suit::Hearts, Rank::Two);
+        let c = Card::new(Suit::Hearts, Rank::Three);
+
+        let mut pile = Pile::of(vec![a]);
+        pile.shuffle_in(vec![b, c]);
+        assert_eq!(pile.count_total(), 3);
+        assert_eq!(pile.count_accessible(), 2);
+        assert_eq!(pile.cards[2], a);
+        assert_ne!(pile.seek(), Some(a));
+    }
+
+    /// Returns the pile with the cards added on top in order, so the last one is on top.
+    fn stacked(ranks: &[Rank]) -> Pile {
+        let mut pile = Pile::new_empty();
+        for &rank in ranks {
+            pile.add_on_top(Card::new(Suit::Spades, rank));
+        }
+        pile
+    }
+
+    fn spades(ranks: &[Rank]) -> Vec<Card> {
+        ranks
+            .iter()
+            .map(|&rank| Card::new(Suit::Spades, rank))
+            .collect()
+    }
+
+    #[test]
+    fn peek_and_pop_n_test() {
+        use Rank::*;
+        let mut pile = stacked(&[Ace, Two, Three]);
+        pile.add_card(Card::new(Suit::Hearts, King));
+        assert_eq!(pile.peek_n(2), spades(&[Three, Two]));
+        assert_eq!(pile.peek_n(5), spades(&[Three, Two, Ace]));
+        assert_eq!(pile.count_accessible(), 3);
+
+        assert_eq!(pile.pop_n(2), spades(&[Three, Two]));
+        assert_eq!(pile.count_total(), 2);
+        assert_eq!(pile.pop_n(5).len(), 2);
+        assert!(pile.is_empty());
+    }
+
+    #[test]
+    fn cut_and_insert_test() {
+        use Rank::*;
+        let mut pile = stacked(&[Ace, Two, Three, Four]);
+        pile.add_card(Card::new(Suit::Hearts, King));
+        pile.cut(1);
+        assert_eq!(pile.peek_n(4), spades(&[Three, Two, Ace, Four]));
+        pile.cut(0);
+        pile.cut(4);
+        assert_eq!(pile.peek_n(4), spades(&[Three, Two, Ace, Four]));
+
+        pile.insert_at(0, Card::new(Suit::Spades, Five));
+        pile.insert_at(2, Card::new(Suit::Spades, Six));
+        pile.insert_at(6, Card::new(Suit::Spades, Seven));
+        assert_eq!(
+            pile.peek_n(7),
+            spades(&[Five, Three, Six, Two, Ace, Four, Seven])
+        );
+        assert_eq!(pile.count_accessible(), 7);
+        assert_eq!(
+            pile.iter_inaccessible().collect::<Vec<_>>(),
+            vec![Card::new(Suit::Hearts, King)]
+        );
+    }
+
+    struct Seat(Hand<SortedCard>);
+
+    impl HasHand for Seat {
+        type CardType = SortedCard;
+
+        fn hand(&self) -> &Hand<SortedCard> {
+            &self.0
+        }
+
+        fn hand_mut(&mut self) -> &mut Hand<SortedCard> {
+            &mut self.0
+        }
+    }
+
+    #[test]
+    fn deal_test() {
+        use Rank::*;
+        let hand = |seat: &Seat| seat.hand().iter().collect::<Vec<_>>();
+
+        let mut seats: Vec<Seat> = (0..3).map(|_| Seat(Hand::new())).collect();
+        let mut pile = stacked(&[Ace, Two, Three, Four, Five, Six, Seven]);
+        pile.deal_round_robin(&mut seats, 2, 1).unwrap();
+        assert_eq!(hand(&seats[1]), spades(&[Four, Seven]));
+        assert_eq!(hand(&seats[2]), spades(&[Three, Six]));
+        assert_eq!(hand(&seats[0]), spades(&[Two, Five]));
+        assert_eq!(
+            pile.deal_round_robin(&mut seats, 1, 0),
+            Err(PileEmptyError { dealt: 1 })
+        );
+
+        let mut seats: Vec<Seat> = (0..2).map(|_| Seat(Hand::new())).collect();
+        let mut pile = stacked(&[Ace, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten]);
+        pile.deal_packets(&mut seats, &[3, 2], 0).unwrap();
+        assert_eq!(hand(&seats[0]), spades(&[Three, Four, Eight, Nine, Ten]));
+        assert_eq!(hand(&seats[1]), spades(&[Ace, Two, Five, Six, Seven]));
+        assert!(pile.is_empty());
+    }
+
+    #[test]
+    fn bottom_and_burn_test() {
+        use Rank::*;
+        let mut pile = stacked(&[Ace, Two, Three, Four]);
+        assert_eq!(pile.bottom(), Some(Card::new(Suit::Spades, Ace)));
+        assert_eq!(pile.pop_bottom(), Some(Card::new(Suit::Spades, Ace)));
+        assert_eq!(pile.bottom(), Some(Card::new(Suit::Spades, Two)));
+        assert_eq!(pile.count_accessible(), 3);
+
+        assert_eq!(pile.burn(2), 2);
+        assert_eq!(pile.iter_accessible().collect::<Vec<_>>(), spades(&[Two]));
+        assert_eq!(pile.iter_inaccessible().count(), 2);
+        assert_eq!(pile.burn(5), 1);
+        assert_eq!(pile.bottom(), None);
+        assert_eq!(pile.count_total(), 3);
+
+        assert!(pile.pop_bottom().is_some());
+        assert_eq!(pile.count_accessible(), 2);
+        assert_eq!(Pile::<Card>::new_empty().pop_bottom(), None);
+    }
 }
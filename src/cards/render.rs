@@ -0,0 +1,356 @@
+use crate::cards::{Card, JokerColor, Suit};
+use std::env;
+use std::io::IsTerminal;
+
+/// Which characters cards are drawn with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Charset {
+    /// Box-drawing characters and suit symbols, like `│ ♥ │`.
+    #[default]
+    Unicode,
+    /// Plain ASCII with suit letters, like `| h |`, for terminals and fonts without the symbols.
+    Ascii,
+}
+
+/// How several cards are placed next to each other.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Layout {
+    /// Every card drawn in full, separated by a space.
+    #[default]
+    Row,
+    /// Cards overlapping like a fan held in hand, with only the top-left corner of all but
+    /// the last card visible. Takes far less room for big hands.
+    Fan,
+}
+
+/// Width and height of a card, borders included.
+const WIDTH: usize = 7;
+const HEIGHT: usize = 5;
+/// Columns of a card left visible by the next card in a [Layout::Fan].
+const FAN_WIDTH: usize = 4;
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// A character of the card art, remembering whether it's printed in red.
+#[derive(Clone, Copy)]
+struct Cell {
+    c: char,
+    red: bool,
+}
+
+type Grid = Vec<Vec<Cell>>;
+
+/// Draws cards as multi-line box art for terminals:
+///
+/// ```text
+/// ┌─────┐
+/// │10   │
+/// │  ♥  │
+/// │   10│
+/// └─────┘
+/// ```
+///
+/// Hearts, diamonds and the red joker are colored with ANSI escapes when [CardArt::colored]
+/// is on. Jokers are labelled like in [Card::to_ascii], `J1` to `J3`, and so are the Tarot
+/// trumps and the Fool.
+///
+/// ```
+/// use cardrs::cards::parse::parse_cards;
+/// use cardrs::cards::render::{CardArt, Charset};
+///
+/// let art = CardArt::new().charset(Charset::Ascii);
+/// let cards = parse_cards("Kd 2s").unwrap();
+/// assert_eq!(art.cards(&cards).lines().nth(1), Some("|K    | |2    |"));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CardArt {
+    charset: Charset,
+    color: bool,
+    layout: Layout,
+}
+
+impl CardArt {
+    /// Unicode art without colors, in a [Layout::Row].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Picks the settings the terminal supports: [Charset::Ascii] unless the locale is UTF-8,
+    /// and colors only when printing to a terminal that isn't `dumb` and `NO_COLOR` isn't set.
+    pub fn from_env() -> Self {
+        let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+        // The first of these that's set decides the character encoding
+        let locale = var("LC_ALL").or(var("LC_CTYPE")).or(var("LANG"));
+        let color = std::io::stdout().is_terminal()
+            && var("NO_COLOR").is_none()
+            && var("TERM").is_some_and(|term| term != "dumb");
+        Self::new()
+            .charset(charset_for_locale(locale.as_deref()))
+            .colored(color)
+    }
+
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Sets whether red cards are colored with ANSI escapes.
+    pub fn colored(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Draws the card face up, one line per row without a trailing newline.
+    pub fn card(&self, card: Card) -> String {
+        self.paint(&self.grid(Some(card)))
+    }
+
+    /// Draws the back of a card, e.g. for the draw pile or another player's hand.
+    pub fn back(&self) -> String {
+        self.paint(&self.grid(None))
+    }
+
+    /// Draws the cards next to each other in the [Layout]. Returns an empty string for no cards.
+    pub fn cards(&self, cards: &[Card]) -> String {
+        let grids: Vec<Grid> = cards.iter().map(|&card| self.grid(Some(card))).collect();
+        self.paint(&self.join(&grids))
+    }
+
+    /// Draws the draw pile with the number of cards left next to the top card of the
+    /// discard pile, and the hand below them.
+    pub fn table(&self, top_card: Card, draw_pile: usize, hand: &[Card]) -> String {
+        let mut pile = self.grid(None);
+        let count = format!("{:^width$}", draw_pile, width = WIDTH);
+        pile.push(count.chars().map(|c| Cell { c, red: false }).collect());
+        let mut top = self.grid(Some(top_card));
+        top.push(blank(WIDTH));
+
+        let mut lines = self.join_with(&[pile, top], Layout::Row);
+        let hand: Vec<Grid> = hand.iter().map(|&card| self.grid(Some(card))).collect();
+        if !hand.is_empty() {
+            lines.push(Vec::new());
+            lines.extend(self.join(&hand));
+        }
+        self.paint(&lines)
+    }
+
+    /// Returns the card drawn cell by cell, or its back for `None`.
+    fn grid(&self, card: Option<Card>) -> Grid {
+        let ascii = self.charset == Charset::Ascii;
+        let (corner, horizontal, vertical) = if ascii {
+            (['+'; 4], '-', '|')
+        } else {
+            (['┌', '┐', '└', '┘'], '─', '│')
+        };
+        let inner = WIDTH - 2;
+        let border = |left, right| {
+            let mut row = vec![left];
+            row.extend(std::iter::repeat_n(horizontal, inner));
+            row.push(right);
+            row.into_iter().map(|c| Cell { c, red: false }).collect()
+        };
+        let side = |content: String, red| {
+            let mut row = vec![Cell {
+                c: vertical,
+                red: false,
+            }];
+            row.extend(content.chars().map(|c| Cell { c, red }));
+            row.push(Cell {
+                c: vertical,
+                red: false,
+            });
+            row
+        };
+
+        let mut grid = vec![border(corner[0], corner[1])];
+        match card {
+            Some(card) => {
+                let (label, symbol) = self.label_and_symbol(card);
+                let red = is_red(card);
+                grid.push(side(format!("{:<inner$}", label), red));
+                grid.push(side(format!("{:^inner$}", symbol), red));
+                grid.push(side(format!("{:>inner$}", label), red));
+            }
+            None => {
+                let fill = if ascii { '#' } else { '░' };
+                for _ in 0..HEIGHT - 2 {
+                    grid.push(side(fill.to_string().repeat(inner), false));
+                }
+            }
+        }
+        grid.push(border(corner[2], corner[3]));
+        grid
+    }
+
+    fn label_and_symbol(&self, card: Card) -> (String, char) {
+        let ascii = self.charset == Charset::Ascii;
+        match (card.suit(), card.rank()) {
+            (Some(suit), Some(rank)) => {
+                let symbol = if ascii {
+                    suit.letter()
+                } else {
+                    suit.unicode_black()
+                };
+                (rank.index().to_string(), symbol)
+            }
+            _ => {
+                let label = card.to_ascii().unwrap_or_else(|_| "??".to_string());
+                (label, if ascii { '*' } else { '★' })
+            }
+        }
+    }
+
+    fn join(&self, grids: &[Grid]) -> Grid {
+        self.join_with(grids, self.layout)
+    }
+
+    /// Places the grids side by side. All of them must have the same height.
+    fn join_with(&self, grids: &[Grid], layout: Layout) -> Grid {
+        let height = grids.first().map_or(0, |grid| grid.len());
+        (0..height)
+            .map(|row| {
+                let mut line = Vec::new();
+                for (i, grid) in grids.iter().enumerate() {
+                    let last = i + 1 == grids.len();
+                    match layout {
+                        Layout::Row => {
+                            line.extend(&grid[row]);
+                            if !last {
+                                line.extend(blank(1));
+                            }
+                        }
+                        Layout::Fan if last => line.extend(&grid[row]),
+                        Layout::Fan => line.extend(&grid[row][..FAN_WIDTH]),
+                    }
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Turns the cells into lines of text, wrapping runs of red cells in escapes if enabled.
+    fn paint(&self, grid: &Grid) -> String {
+        let lines: Vec<String> = grid
+            .iter()
+            .map(|row| {
+                let mut line = String::new();
+                let mut red = false;
+                for cell in row {
+                    let cell_red = self.color && cell.red;
+                    if cell_red != red {
+                        line.push_str(if cell_red { RED } else { RESET });
+                        red = cell_red;
+                    }
+                    line.push(cell.c);
+                }
+                if red {
+                    line.push_str(RESET);
+                }
+                line.trim_end().to_string()
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+fn blank(width: usize) -> Vec<Cell> {
+    vec![Cell { c: ' ', red: false }; width]
+}
+
+fn is_red(card: Card) -> bool {
+    matches!(card.suit(), Some(Suit::Hearts | Suit::Diamonds))
+        || card.joker_color() == Some(JokerColor::Red)
+}
+
+fn charset_for_locale(locale: Option<&str>) -> Charset {
+    let utf8 = locale.is_some_and(|locale| {
+        let locale = locale.to_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    });
+    if utf8 {
+        Charset::Unicode
+    } else {
+        Charset::Ascii
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse::parse_cards;
+
+    #[test]
+    fn single_card() {
+        let ten = "10h".parse().unwrap();
+        assert_eq!(
+            CardArt::new().card(ten),
+            "┌─────┐\n│10   │\n│  ♥  │\n│   10│\n└─────┘"
+        );
+        assert_eq!(
+            CardArt::new().charset(Charset::Ascii).card(ten),
+            "+-----+\n|10   |\n|  h  |\n|   10|\n+-----+"
+        );
+        assert_eq!(
+            CardArt::new().colored(true).card(ten).lines().nth(2),
+            Some("│\x1b[31m  ♥  \x1b[0m│")
+        );
+        assert_eq!(
+            CardArt::new().colored(true).card("Ks".parse().unwrap()),
+            CardArt::new().card("Ks".parse().unwrap())
+        );
+        assert_eq!(
+            CardArt::new().card(Card::FOOL).lines().nth(3),
+            Some("│   T0│")
+        );
+        assert_eq!(
+            CardArt::new().charset(Charset::Ascii).back().lines().nth(1),
+            Some("|#####|")
+        );
+    }
+
+    #[test]
+    fn layouts() {
+        let cards = parse_cards("As Kd J1").unwrap();
+        let row = CardArt::new().cards(&cards);
+        assert_eq!(row.lines().count(), HEIGHT);
+        assert_eq!(row.lines().nth(1), Some("│A    │ │K    │ │J1   │"));
+
+        let fan = CardArt::new()
+            .layout(Layout::Fan)
+            .colored(true)
+            .cards(&cards);
+        assert_eq!(
+            fan.lines().nth(1),
+            Some("│A  │\x1b[31mK  \x1b[0m│\x1b[31mJ1   \x1b[0m│")
+        );
+        assert_eq!(fan.lines().next(), Some("┌───┌───┌─────┐"));
+        assert_eq!(CardArt::new().cards(&[]), "");
+    }
+
+    #[test]
+    fn table() {
+        let hand = parse_cards("As 2s").unwrap();
+        let table = CardArt::new()
+            .charset(Charset::Ascii)
+            .table("Qc".parse().unwrap(), 30, &hand);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[1], "|#####| |Q    |");
+        assert_eq!(lines[5], "  30");
+        assert_eq!(lines[6], "");
+        assert_eq!(lines[8], "|A    | |2    |");
+    }
+
+    #[test]
+    fn environment() {
+        assert_eq!(charset_for_locale(Some("en_US.UTF-8")), Charset::Unicode);
+        assert_eq!(charset_for_locale(Some("pl_PL.utf8")), Charset::Unicode);
+        assert_eq!(charset_for_locale(Some("C")), Charset::Ascii);
+        assert_eq!(charset_for_locale(None), Charset::Ascii);
+    }
+}
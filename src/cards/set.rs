@@ -0,0 +1,673 @@
+use crate::cards::{Card, JokerColor, Rank, Suit};
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+/// Bits per suit: the 13 standard ranks and the knight.
+const SUIT_BITS: usize = 14;
+const FIRST_JOKER: usize = 4 * SUIT_BITS;
+/// Number of cards a [CardSet] holds: 56 suited cards with knights and three jokers.
+const SET_CARDS: usize = FIRST_JOKER + 3;
+/// The Fool comes after the cards of a [CardSet], followed by trumps 1 to 21.
+const FOOL: usize = SET_CARDS;
+/// Number of distinct cards, counting the 21 trumps and the Fool.
+const DISTINCT_CARDS: usize = FOOL + 22;
+const ALL_BITS: u64 = (1 << SET_CARDS) - 1;
+/// The Fool in bit 0 and trump `n` in bit `n`.
+const ALL_TRUMP_BITS: u32 = (1 << 22) - 1;
+/// One bit in the first position of every suit.
+const SUIT_STRIDE: u64 = 1 | 1 << SUIT_BITS | 1 << (2 * SUIT_BITS) | 1 << (3 * SUIT_BITS);
+
+/// Returns the position of the card in a [CardCounts], ordered like [SortedCard](crate::cards::hand::SortedCard).
+/// Positions below [SET_CARDS] are also the card's bit in a [CardSet].
+fn bit_index(card: Card) -> usize {
+    match (card.suit(), card.rank(), card.joker_color()) {
+        (Some(suit), Some(rank), _) => suit as usize * SUIT_BITS + rank.position(),
+        (_, _, Some(color)) => FIRST_JOKER + (color as usize - JokerColor::Red as usize),
+        _ => FOOL + card.trump_number().unwrap_or(0) as usize,
+    }
+}
+
+fn card_at(index: usize) -> Card {
+    if index >= FOOL {
+        match index - FOOL {
+            0 => Card::FOOL,
+            number => Card::new_trump(number as u8).unwrap(),
+        }
+    } else if index >= FIRST_JOKER {
+        let color = (index - FIRST_JOKER) as u8 + JokerColor::Red as u8;
+        Card::new_joker(JokerColor::try_from(color).unwrap())
+    } else {
+        Card::new(
+            Suit::try_from((index / SUIT_BITS) as u8).unwrap(),
+            Rank::iter_with_knight().as_slice()[index % SUIT_BITS],
+        )
+    }
+}
+
+/// Returns the card's bit in a [CardSet], or `None` for trumps and the Fool.
+fn set_bit(card: Card) -> Option<u64> {
+    let index = bit_index(card);
+    (index < SET_CARDS).then(|| 1 << index)
+}
+
+/// Returns the card's bit among the trumps of a [TarotSet], or `None` for other cards.
+fn trump_bit(card: Card) -> Option<u32> {
+    if card.is_fool() {
+        Some(1)
+    } else {
+        card.trump_number().map(|number| 1 << number)
+    }
+}
+
+/// A set of distinct cards stored as a 64-bit mask, one bit per card: the 52 standard cards,
+/// three jokers and the knights of Tarot decks. Every operation is a single integer
+/// instruction, cheap enough for bots simulating many games.
+///
+/// The trumps and the Fool don't fit; use a [TarotSet] for Tarot decks. Inserting them
+/// panics and a `CardSet` never contains them.
+///
+/// Set operations are available as `|` (union), `&` (intersection), `-` (difference)
+/// and `!` (complement within [CardSet::ALL]).
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+
+    /// All 52 standard cards, three jokers and the knights of Tarot decks.
+    pub const ALL: CardSet = CardSet(ALL_BITS);
+
+    pub const JOKERS: CardSet = CardSet(0b111 << FIRST_JOKER);
+
+    pub const KNIGHTS: CardSet = CardSet(SUIT_STRIDE << Rank::Knight.position());
+
+    /// The 52 cards of a standard deck.
+    pub const STANDARD: CardSet = CardSet(((1 << FIRST_JOKER) - 1) & !CardSet::KNIGHTS.0);
+
+    pub fn of_suit(suit: Suit) -> CardSet {
+        CardSet(((1 << SUIT_BITS) - 1) << (suit as usize * SUIT_BITS))
+    }
+
+    pub fn of_rank(rank: Rank) -> CardSet {
+        CardSet(SUIT_STRIDE << rank.position())
+    }
+
+    /// # Panics
+    ///
+    /// Panics for trumps and the Fool.
+    pub fn single(card: Card) -> CardSet {
+        CardSet(Self::bit(card))
+    }
+
+    /// Inserts the card, returning whether it wasn't in the set yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics for trumps and the Fool.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let had = self.contains(card);
+        self.0 |= Self::bit(card);
+        !had
+    }
+
+    /// Removes the card, returning whether it was in the set.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let had = self.contains(card);
+        self.0 &= !set_bit(card).unwrap_or(0);
+        had
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & set_bit(card).unwrap_or(0) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_subset(&self, other: CardSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn cards_of_suit(&self, suit: Suit) -> CardSet {
+        *self & CardSet::of_suit(suit)
+    }
+
+    pub fn cards_of_rank(&self, rank: Rank) -> CardSet {
+        *self & CardSet::of_rank(rank)
+    }
+
+    pub fn jokers(&self) -> CardSet {
+        *self & CardSet::JOKERS
+    }
+
+    /// Returns the cards matching the predicate.
+    pub fn filter<F: Fn(Card) -> bool>(&self, predicate: F) -> CardSet {
+        self.iter().filter(|&card| predicate(card)).collect()
+    }
+
+    /// Iterates over the cards, ordered like [SortedCard](crate::cards::hand::SortedCard).
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+
+    fn bit(card: Card) -> u64 {
+        set_bit(card)
+            .unwrap_or_else(|| panic!("{card:?} doesn't fit in a CardSet, use a TarotSet instead"))
+    }
+}
+
+impl fmt::Debug for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 & rhs.0)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 & !rhs.0)
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    fn not(self) -> CardSet {
+        CardSet(!self.0 & ALL_BITS)
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = CardSet::EMPTY;
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(card_at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+/// A set of distinct cards from any deck, Tarot trumps and the Fool included: a [CardSet]
+/// for the suited cards and jokers, and a 32-bit mask for the trumps.
+///
+/// It's twice the size of a [CardSet], so prefer that one for decks without trumps.
+/// Set operations are available as `|`, `&`, `-` and `!` (complement within [TarotSet::ALL]).
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct TarotSet {
+    cards: CardSet,
+    trumps: u32,
+}
+
+impl TarotSet {
+    pub const EMPTY: TarotSet = TarotSet {
+        cards: CardSet::EMPTY,
+        trumps: 0,
+    };
+
+    /// All 81 distinct cards: [CardSet::ALL], the 21 trumps and the Fool.
+    pub const ALL: TarotSet = TarotSet {
+        cards: CardSet::ALL,
+        trumps: ALL_TRUMP_BITS,
+    };
+
+    /// The 21 trumps, without the Fool.
+    pub const TRUMPS: TarotSet = TarotSet {
+        cards: CardSet::EMPTY,
+        trumps: ALL_TRUMP_BITS & !1,
+    };
+
+    /// Inserts the card, returning whether it wasn't in the set yet.
+    pub fn insert(&mut self, card: Card) -> bool {
+        match trump_bit(card) {
+            Some(bit) => {
+                let had = self.trumps & bit != 0;
+                self.trumps |= bit;
+                !had
+            }
+            None => self.cards.insert(card),
+        }
+    }
+
+    /// Removes the card, returning whether it was in the set.
+    pub fn remove(&mut self, card: Card) -> bool {
+        match trump_bit(card) {
+            Some(bit) => {
+                let had = self.trumps & bit != 0;
+                self.trumps &= !bit;
+                had
+            }
+            None => self.cards.remove(card),
+        }
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        match trump_bit(card) {
+            Some(bit) => self.trumps & bit != 0,
+            None => self.cards.contains(card),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len() + self.trumps.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty() && self.trumps == 0
+    }
+
+    pub fn is_subset(&self, other: TarotSet) -> bool {
+        (*self - other).is_empty()
+    }
+
+    /// Returns the cards other than trumps and the Fool.
+    pub fn cards(&self) -> CardSet {
+        self.cards
+    }
+
+    /// Iterates over the cards, ordered like [SortedCard](crate::cards::hand::SortedCard).
+    pub fn iter(&self) -> TarotSetIter {
+        TarotSetIter {
+            cards: self.cards.iter(),
+            trumps: self.trumps,
+        }
+    }
+}
+
+impl From<CardSet> for TarotSet {
+    fn from(cards: CardSet) -> Self {
+        TarotSet { cards, trumps: 0 }
+    }
+}
+
+impl fmt::Debug for TarotSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for TarotSet {
+    type Output = TarotSet;
+
+    fn bitor(self, rhs: TarotSet) -> TarotSet {
+        TarotSet {
+            cards: self.cards | rhs.cards,
+            trumps: self.trumps | rhs.trumps,
+        }
+    }
+}
+
+impl BitAnd for TarotSet {
+    type Output = TarotSet;
+
+    fn bitand(self, rhs: TarotSet) -> TarotSet {
+        TarotSet {
+            cards: self.cards & rhs.cards,
+            trumps: self.trumps & rhs.trumps,
+        }
+    }
+}
+
+impl Sub for TarotSet {
+    type Output = TarotSet;
+
+    fn sub(self, rhs: TarotSet) -> TarotSet {
+        TarotSet {
+            cards: self.cards - rhs.cards,
+            trumps: self.trumps & !rhs.trumps,
+        }
+    }
+}
+
+impl Not for TarotSet {
+    type Output = TarotSet;
+
+    fn not(self) -> TarotSet {
+        TarotSet {
+            cards: !self.cards,
+            trumps: !self.trumps & ALL_TRUMP_BITS,
+        }
+    }
+}
+
+impl FromIterator<Card> for TarotSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = TarotSet::EMPTY;
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+impl IntoIterator for TarotSet {
+    type Item = Card;
+    type IntoIter = TarotSetIter;
+
+    fn into_iter(self) -> TarotSetIter {
+        self.iter()
+    }
+}
+
+pub struct TarotSetIter {
+    cards: CardSetIter,
+    trumps: u32,
+}
+
+impl Iterator for TarotSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if let Some(card) = self.cards.next() {
+            return Some(card);
+        }
+        if self.trumps == 0 {
+            return None;
+        }
+        let index = self.trumps.trailing_zeros() as usize;
+        self.trumps &= self.trumps - 1;
+        Some(card_at(FOOL + index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.cards.size_hint().0 + self.trumps.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+const TOO_MANY_COPIES: &str = "a card can be counted at most u16::MAX times";
+
+/// A multiset of cards, for when several decks are played with and a card can appear more
+/// than once. Keeps a [TarotSet] of the cards present for fast queries.
+///
+/// Each card can be counted up to [u16::MAX] times, like the copies of a
+/// [PhysicalCard](crate::cards::physical::PhysicalCard); going over that panics.
+///
+/// With two bytes for each of the 81 distinct cards, the counts take up to 184 bytes, so
+/// prefer a [CardSet] where the number of copies doesn't matter.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct CardCounts {
+    counts: [u16; DISTINCT_CARDS],
+    present: TarotSet,
+}
+
+impl Default for CardCounts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CardCounts {
+    pub fn new() -> Self {
+        CardCounts {
+            counts: [0; DISTINCT_CARDS],
+            present: TarotSet::EMPTY,
+        }
+    }
+
+    pub fn insert(&mut self, card: Card) {
+        let count = &mut self.counts[bit_index(card)];
+        *count = count.checked_add(1).expect(TOO_MANY_COPIES);
+        self.present.insert(card);
+    }
+
+    /// Removes one copy of the card, returning whether there was any.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let count = &mut self.counts[bit_index(card)];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        if *count == 0 {
+            self.present.remove(card);
+        }
+        true
+    }
+
+    pub fn count(&self, card: Card) -> usize {
+        self.counts[bit_index(card)] as usize
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.present.contains(card)
+    }
+
+    /// Returns the distinct cards present at least once, other than trumps and the Fool.
+    pub fn set(&self) -> CardSet {
+        self.present.cards()
+    }
+
+    /// Returns the distinct cards present at least once, trumps and the Fool included.
+    pub fn tarot_set(&self) -> TarotSet {
+        self.present
+    }
+
+    /// Returns the total number of cards, counting every copy.
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|&count| count as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.present.is_empty()
+    }
+
+    /// Returns the cards present in either multiset, adding up the copies.
+    pub fn union(&self, other: &CardCounts) -> CardCounts {
+        self.combine(other, |a, b| a.checked_add(b).expect(TOO_MANY_COPIES))
+    }
+
+    /// Returns the copies present in both multisets.
+    pub fn intersection(&self, other: &CardCounts) -> CardCounts {
+        self.combine(other, u16::min)
+    }
+
+    /// Returns the copies left after taking away those in the other multiset.
+    pub fn difference(&self, other: &CardCounts) -> CardCounts {
+        self.combine(other, u16::saturating_sub)
+    }
+
+    fn combine<F: Fn(u16, u16) -> u16>(&self, other: &CardCounts, op: F) -> CardCounts {
+        let mut result = CardCounts::new();
+        for card in self.present | other.present {
+            let index = bit_index(card);
+            result.counts[index] = op(self.counts[index], other.counts[index]);
+            if result.counts[index] > 0 {
+                result.present.insert(card);
+            }
+        }
+        result
+    }
+
+    /// Iterates over the cards, repeating each as many times as it's present.
+    pub fn iter(&self) -> impl Iterator<Item = Card> + '_ {
+        self.present
+            .iter()
+            .flat_map(|card| std::iter::repeat_n(card, self.count(card)))
+    }
+}
+
+impl fmt::Debug for CardCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Card> for CardCounts {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut counts = CardCounts::new();
+        for card in iter {
+            counts.insert(card);
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::{generate_n_decks, DeckSpec};
+    use crate::cards::parse::parse_cards;
+
+    fn set(cards: &str) -> CardSet {
+        parse_cards(cards).unwrap().into_iter().collect()
+    }
+
+    #[test]
+    fn card_set() {
+        let all: CardSet = generate_n_decks(1, 3).unwrap().into_iter().collect();
+        assert_eq!(all, CardSet::STANDARD | CardSet::JOKERS);
+        assert_eq!(all.iter().count(), 55);
+        assert_eq!(!CardSet::EMPTY, CardSet::ALL);
+
+        assert_eq!(
+            CardSet::ALL,
+            CardSet::STANDARD | CardSet::KNIGHTS | CardSet::JOKERS
+        );
+        assert_eq!(CardSet::of_rank(Rank::Knight), CardSet::KNIGHTS);
+        assert_eq!(CardSet::ALL.cards_of_suit(Suit::Hearts).len(), 14);
+        assert!(!CardSet::ALL.contains(Card::FOOL));
+
+        let a = set("As Kh 2h J1");
+        let b = set("Kh 3d J1 J2");
+        assert_eq!(a | b, set("As Kh 2h J1 3d J2"));
+        assert_eq!(a & b, set("Kh J1"));
+        assert_eq!(a - b, set("As 2h"));
+        assert_eq!(a.cards_of_suit(Suit::Hearts), set("Kh 2h"));
+        assert_eq!(a.cards_of_rank(Rank::King), set("Kh"));
+        assert_eq!(b.jokers(), set("J1 J2"));
+        assert_eq!(a.filter(|card| card.rank() == Some(Rank::Ace)), set("As"));
+        assert_eq!(
+            a.iter().collect::<Vec<_>>(),
+            parse_cards("As 2h Kh J1").unwrap()
+        );
+        assert!(set("Kh").is_subset(a));
+    }
+
+    #[test]
+    fn tarot_set() {
+        let deck = DeckSpec::tarot().generate().unwrap();
+        let tarot: TarotSet = deck.iter().copied().collect();
+        assert_eq!(tarot.len(), 78);
+        assert_eq!(tarot | TarotSet::from(CardSet::JOKERS), TarotSet::ALL);
+        assert_eq!(!tarot, TarotSet::from(CardSet::JOKERS));
+        assert_eq!(tarot.iter().collect::<Vec<_>>(), deck);
+        assert_eq!(tarot.iter().size_hint(), (78, Some(78)));
+        assert_eq!(tarot.cards(), CardSet::STANDARD | CardSet::KNIGHTS);
+
+        let fool = TarotSet::from_iter([Card::FOOL]);
+        assert_eq!(tarot - tarot.cards().into(), TarotSet::TRUMPS | fool);
+        assert!(TarotSet::TRUMPS.is_subset(tarot));
+        assert!(fool.contains(Card::FOOL));
+        assert!((TarotSet::TRUMPS & fool).is_empty());
+
+        let mut set = TarotSet::from(set("Kh"));
+        let trump = Card::new_trump(21).unwrap();
+        assert!(set.insert(trump));
+        assert!(!set.insert(trump));
+        assert_eq!(set.len(), 2);
+        assert!(set.remove(trump));
+        assert!(!set.contains(trump));
+    }
+
+    #[test]
+    #[should_panic(expected = "use a TarotSet")]
+    fn card_set_rejects_trumps() {
+        CardSet::single(Card::new_trump(1).unwrap());
+    }
+
+    #[test]
+    fn card_counts() {
+        let mut counts: CardCounts = generate_n_decks(2, 1).unwrap().into_iter().collect();
+        assert_eq!(counts.len(), 106);
+        let king = Card::new(Suit::Hearts, Rank::King);
+        assert_eq!(counts.count(king), 2);
+        assert!(counts.remove(king));
+        assert!(counts.remove(king));
+        assert!(!counts.remove(king));
+        assert!(!counts.contains(king));
+
+        let a: CardCounts = parse_cards("Kh Kh As").unwrap().into_iter().collect();
+        let b: CardCounts = parse_cards("Kh 2c").unwrap().into_iter().collect();
+        assert_eq!(a.union(&b).count(king), 3);
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![king]);
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            parse_cards("As Kh").unwrap()
+        );
+
+        let decks: CardCounts = generate_n_decks(300, 0).unwrap().into_iter().collect();
+        assert_eq!(decks.count(king), 300);
+        assert_eq!(decks.union(&decks).count(king), 600);
+
+        let tarot: CardCounts = DeckSpec::tarot().generate().unwrap().into_iter().collect();
+        assert_eq!(tarot.count(Card::FOOL), 1);
+        assert_eq!(tarot.tarot_set().len(), 78);
+        assert_eq!(tarot.set().len(), 56);
+        assert_eq!(tarot.difference(&tarot).len(), 0);
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(std::mem::size_of::<CardSet>(), 8);
+        assert_eq!(std::mem::size_of::<TarotSet>(), 16);
+        assert!(std::mem::size_of::<CardCounts>() <= 184);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most u16::MAX")]
+    fn card_counts_overflow() {
+        let king = Card::new(Suit::Hearts, Rank::King);
+        let counts: CardCounts = std::iter::repeat_n(king, u16::MAX as usize).collect();
+        counts.union(&CardCounts::from_iter([king]));
+    }
+}
@@ -0,0 +1,180 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// How a [Pile](crate::cards::pile::Pile) gets shuffled.
+///
+/// [ShuffleModel::Uniform] gives every order the same chance. The other models imitate
+/// shuffles done by hand, which leave some of the original order behind unless repeated
+/// enough times; [mean_rising_sequences] shows how far from uniform they get.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ShuffleModel {
+    /// A perfect Fisher–Yates shuffle.
+    #[default]
+    Uniform,
+    /// Riffles following the Gilbert–Shannon–Reeds model: the deck is cut about in half,
+    /// and the halves are interleaved, dropping cards from each half with a chance
+    /// proportional to its size. Seven riffles are enough for a 52-card deck.
+    Riffle { times: u32 },
+    /// Overhand shuffles: small packets are slid off one after another, which reverses
+    /// their order while keeping the order within each packet.
+    Overhand { times: u32 },
+    /// Strip cuts: the deck is split into the given number of strips at random points,
+    /// and the strips are stacked in reverse order.
+    StripCut { strips: u32 },
+}
+
+/// Chance that an overhand shuffle splits the deck between two cards,
+/// giving packets of five cards on average.
+const OVERHAND_CUT_CHANCE: f64 = 0.2;
+
+impl ShuffleModel {
+    pub fn shuffle<T: Clone, R: Rng + ?Sized>(&self, items: &mut [T], rng: &mut R) {
+        match *self {
+            ShuffleModel::Uniform => items.shuffle(rng),
+            ShuffleModel::Riffle { times } => {
+                for _ in 0..times {
+                    riffle(items, rng);
+                }
+            }
+            ShuffleModel::Overhand { times } => {
+                for _ in 0..times {
+                    let cuts = (1..items.len())
+                        .filter(|_| rng.gen_bool(OVERHAND_CUT_CHANCE))
+                        .collect();
+                    reverse_packets(items, cuts);
+                }
+            }
+            ShuffleModel::StripCut { strips } => {
+                let mut cuts: Vec<usize> = (1..strips)
+                    .map(|_| rng.gen_range(0..=items.len()))
+                    .collect();
+                cuts.sort();
+                reverse_packets(items, cuts);
+            }
+        }
+    }
+}
+
+fn riffle<T: Clone, R: Rng + ?Sized>(items: &mut [T], rng: &mut R) {
+    let len = items.len();
+    let cut = (0..len).filter(|_| rng.gen_bool(0.5)).count();
+    let mut order = Vec::with_capacity(len);
+    let (mut left, mut right) = (0, cut);
+    while order.len() < len {
+        let left_remaining = cut - left;
+        let right_remaining = len - right;
+        if rng.gen_range(0..left_remaining + right_remaining) < left_remaining {
+            order.push(left);
+            left += 1;
+        } else {
+            order.push(right);
+            right += 1;
+        }
+    }
+    apply_order(items, order);
+}
+
+/// Splits the items at the sorted cut points and stacks the packets in reverse order.
+fn reverse_packets<T: Clone>(items: &mut [T], cuts: Vec<usize>) {
+    let mut bounds = vec![0];
+    bounds.extend(cuts);
+    bounds.push(items.len());
+    let order = bounds
+        .windows(2)
+        .rev()
+        .flat_map(|packet| packet[0]..packet[1])
+        .collect();
+    apply_order(items, order);
+}
+
+/// Rearranges the items so `items[i]` becomes the item previously at `order[i]`.
+fn apply_order<T: Clone>(items: &mut [T], order: Vec<usize>) {
+    let reordered: Vec<T> = order.into_iter().map(|i| items[i].clone()).collect();
+    items.clone_from_slice(&reordered);
+}
+
+/// Counts the rising sequences of a permutation, where `permutation[i]` is the original
+/// position of the item now at position `i`.
+///
+/// A rising sequence is a maximal run of consecutive original positions appearing in
+/// increasing order. An unshuffled deck has one, a single riffle leaves at most two,
+/// and a uniformly shuffled deck of `n` cards has `(n + 1) / 2` on average.
+pub fn rising_sequences(permutation: &[usize]) -> usize {
+    if permutation.is_empty() {
+        return 0;
+    }
+    let mut position = vec![0; permutation.len()];
+    for (i, &original) in permutation.iter().enumerate() {
+        position[original] = i;
+    }
+    1 + position.windows(2).filter(|pair| pair[1] < pair[0]).count()
+}
+
+/// Returns the average number of [rising_sequences] the model leaves in a deck of
+/// `deck_size` cards over the given number of trials.
+pub fn mean_rising_sequences<R: Rng + ?Sized>(
+    model: ShuffleModel,
+    deck_size: usize,
+    trials: usize,
+    rng: &mut R,
+) -> f64 {
+    let total: usize = (0..trials)
+        .map(|_| {
+            let mut permutation: Vec<usize> = (0..deck_size).collect();
+            model.shuffle(&mut permutation, rng);
+            rising_sequences(&permutation)
+        })
+        .sum();
+    total as f64 / trials as f64
+}
+
+/// Returns the average number of [rising_sequences] in a uniformly shuffled deck.
+pub fn uniform_rising_sequences(deck_size: usize) -> f64 {
+    (deck_size + 1) as f64 / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn models_keep_items() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for model in [
+            ShuffleModel::Uniform,
+            ShuffleModel::Riffle { times: 3 },
+            ShuffleModel::Overhand { times: 3 },
+            ShuffleModel::StripCut { strips: 4 },
+        ] {
+            let mut items: Vec<usize> = (0..52).collect();
+            model.shuffle(&mut items, &mut rng);
+            items.sort();
+            assert_eq!(items, (0..52).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn rising_sequence_counts() {
+        assert_eq!(rising_sequences(&[0, 1, 2, 3]), 1);
+        assert_eq!(rising_sequences(&[3, 2, 1, 0]), 4);
+        assert_eq!(rising_sequences(&[0, 2, 1, 3]), 2);
+        assert_eq!(rising_sequences(&[]), 0);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let mut permutation: Vec<usize> = (0..52).collect();
+            ShuffleModel::Riffle { times: 1 }.shuffle(&mut permutation, &mut rng);
+            assert!(rising_sequences(&permutation) <= 2);
+        }
+
+        let uniform = uniform_rising_sequences(52);
+        let mut mean = |model| mean_rising_sequences(model, 52, 200, &mut rng);
+        assert!((mean(ShuffleModel::Uniform) - uniform).abs() < 1.0);
+        assert!((mean(ShuffleModel::Riffle { times: 7 }) - uniform).abs() < 3.0);
+        assert!((mean(ShuffleModel::Riffle { times: 12 }) - uniform).abs() < 1.0);
+        assert!(mean(ShuffleModel::Riffle { times: 2 }) < 5.0);
+        assert!(mean(ShuffleModel::StripCut { strips: 4 }) < uniform / 2.0);
+    }
+}
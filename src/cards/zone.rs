@@ -0,0 +1,414 @@
+use crate::cards::hand::{Hand, SortedCard};
+use crate::cards::pile::Pile;
+use crate::cards::set::CardCounts;
+use crate::cards::Card;
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+/// A place cards can be in, like a hand or a pile. Cards move between zones with
+/// [move_cards] or within a [Transaction], so none are lost or duplicated on the way.
+///
+/// Zones hold [Card]s by default. Zones of [PhysicalCard](crate::cards::physical::PhysicalCard)s
+/// take and put the exact copies, while [Zone::card_counts] counts only their faces.
+pub trait Zone<C = Card> {
+    /// Removes the cards from the zone. If not all of them can be taken,
+    /// returns an error and leaves the zone untouched.
+    ///
+    /// The cards are returned top first, so [Zone::put] puts them back in the same order.
+    fn take(&mut self, take: Take<C>) -> Result<Vec<C>, ZoneError>;
+
+    /// Adds the cards to the zone. In ordered zones the first card ends up on top.
+    fn put(&mut self, cards: Vec<C>);
+
+    fn card_counts(&self) -> CardCounts;
+
+    /// Saves the whole state of the zone, e.g. the order of a pile, for [Zone::restore].
+    fn snapshot(&self) -> Box<dyn Any>;
+
+    /// Puts the zone back into the state of the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot was taken from another kind of zone.
+    fn restore(&mut self, snapshot: Box<dyn Any>);
+}
+
+pub(crate) const WRONG_SNAPSHOT: &str = "the snapshot was taken from the same kind of zone";
+
+/// Which cards to [take](Zone::take) from a zone.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Take<'a, C = Card> {
+    /// The given number of cards from the top. For a [Hand], the first cards in its order.
+    Top(usize),
+    Cards(&'a [C]),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ZoneError {
+    NotEnoughCards { requested: usize, available: usize },
+    CardMissing(Card),
+    UnknownZone(ZoneId),
+}
+
+impl Display for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZoneError::NotEnoughCards {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Can't take {} cards, only {} are there.",
+                requested, available
+            ),
+            ZoneError::CardMissing(card) => write!(f, "The card {} isn't there.", card),
+            ZoneError::UnknownZone(zone) => write!(f, "There is no zone {:?}.", zone),
+        }
+    }
+}
+
+impl Error for ZoneError {}
+
+/// Finds a different position of each wanted card in `cards`, searching from the end.
+/// Returns the positions from the highest down, so they can be removed one by one.
+pub(crate) fn positions_of<C: Copy + Eq + Into<Card>>(
+    cards: &[C],
+    wanted: &[C],
+) -> Result<Vec<usize>, ZoneError> {
+    let mut positions = Vec::with_capacity(wanted.len());
+    for &card in wanted {
+        let position = (0..cards.len())
+            .rev()
+            .find(|&i| cards[i] == card && !positions.contains(&i))
+            .ok_or(ZoneError::CardMissing(card.into()))?;
+        positions.push(position);
+    }
+    positions.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(positions)
+}
+
+/// A pile of cards with the top at the end, like the cards played to the table.
+impl<C: Copy + Eq + Into<Card> + 'static> Zone<C> for Vec<C> {
+    fn take(&mut self, take: Take<C>) -> Result<Vec<C>, ZoneError> {
+        match take {
+            Take::Top(n) => {
+                if n > self.len() {
+                    return Err(ZoneError::NotEnoughCards {
+                        requested: n,
+                        available: self.len(),
+                    });
+                }
+                Ok(self.drain(self.len() - n..).rev().collect())
+            }
+            Take::Cards(cards) => {
+                for position in positions_of(self, cards)? {
+                    self.remove(position);
+                }
+                Ok(cards.to_vec())
+            }
+        }
+    }
+
+    fn put(&mut self, cards: Vec<C>) {
+        self.extend(cards.into_iter().rev());
+    }
+
+    fn card_counts(&self) -> CardCounts {
+        self.iter().map(|&card| card.into()).collect()
+    }
+
+    fn snapshot(&self) -> Box<dyn Any> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, snapshot: Box<dyn Any>) {
+        *self = *snapshot.downcast().expect(WRONG_SNAPSHOT);
+    }
+}
+
+/// Moves the cards between two zones, either completely or not at all.
+pub fn move_cards<C: Clone>(
+    from: &mut dyn Zone<C>,
+    to: &mut dyn Zone<C>,
+    take: Take<C>,
+) -> Result<Vec<C>, ZoneError> {
+    let cards = from.take(take)?;
+    to.put(cards.clone());
+    Ok(cards)
+}
+
+/// Identifies a zone in a [ZoneMap].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ZoneId {
+    /// The hand of the player in the given seat.
+    Hand(usize),
+    DrawPile,
+    Discard,
+    /// Cards played face up in front of the players, like a trick.
+    Table,
+}
+
+/// A set of zones making up a game, to move cards between them in a [Transaction].
+pub trait ZoneMap<C = Card> {
+    fn zone_mut(&mut self, id: ZoneId) -> Option<&mut dyn Zone<C>>;
+}
+
+/// A series of moves that is rolled back as a whole if any of them fails.
+/// Start one with [transaction].
+pub struct Transaction<'a, M: ZoneMap<C> + ?Sized, C = Card> {
+    zones: &'a mut M,
+    /// Snapshots of the zones touched so far, taken before their first move.
+    saved: Vec<(ZoneId, Box<dyn Any>)>,
+    card_type: PhantomData<C>,
+}
+
+impl<M: ZoneMap<C> + ?Sized, C: Clone> Transaction<'_, M, C> {
+    /// Moves the cards between the zones, returning the moved cards.
+    pub fn move_cards(
+        &mut self,
+        from: ZoneId,
+        to: ZoneId,
+        take: Take<C>,
+    ) -> Result<Vec<C>, ZoneError> {
+        for id in [from, to] {
+            let zone = self.zones.zone_mut(id).ok_or(ZoneError::UnknownZone(id))?;
+            if !self.saved.iter().any(|(saved, _)| *saved == id) {
+                self.saved.push((id, zone.snapshot()));
+            }
+        }
+        let cards = self.zones.zone_mut(from).unwrap().take(take)?;
+        self.zones.zone_mut(to).unwrap().put(cards.clone());
+        Ok(cards)
+    }
+
+    /// Restores every zone touched by the transaction.
+    fn rollback(&mut self) {
+        for (id, snapshot) in self.saved.drain(..) {
+            self.zones.zone_mut(id).unwrap().restore(snapshot);
+        }
+    }
+}
+
+/// Runs the moves in `f`. If `f` returns an error, every zone it moved cards from or to is
+/// restored to how it was before, including the order of the cards and the reshuffles.
+pub fn transaction<M, C, R, F>(zones: &mut M, f: F) -> Result<R, ZoneError>
+where
+    M: ZoneMap<C> + ?Sized,
+    C: Clone,
+    F: FnOnce(&mut Transaction<M, C>) -> Result<R, ZoneError>,
+{
+    let mut transaction = Transaction {
+        zones,
+        saved: Vec::new(),
+        card_type: PhantomData,
+    };
+    let result = f(&mut transaction);
+    if result.is_err() {
+        transaction.rollback();
+    }
+    result
+}
+
+/// Cards that are missing or present more times than the decks have them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConservationError {
+    pub missing: Vec<Card>,
+    pub extra: Vec<Card>,
+}
+
+impl Display for ConservationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Cards are missing: {:?}, cards are extra: {:?}.",
+            self.missing, self.extra
+        )
+    }
+}
+
+impl Error for ConservationError {}
+
+/// Checks that the zones together hold exactly the expected cards.
+pub fn check_conservation<C>(
+    expected: &CardCounts,
+    zones: &[&dyn Zone<C>],
+) -> Result<(), ConservationError> {
+    let actual = zones.iter().fold(CardCounts::new(), |all, zone| {
+        all.union(&zone.card_counts())
+    });
+    if actual == *expected {
+        return Ok(());
+    }
+    Err(ConservationError {
+        missing: expected.difference(&actual).iter().collect(),
+        extra: actual.difference(expected).iter().collect(),
+    })
+}
+
+/// The usual zones of a card game: the players' hands, the draw pile, the discard and the table.
+///
+/// Remembers the cards it was created with and, in debug builds, checks after every
+/// [Zones::transaction] that each of them is still in exactly one place.
+pub struct Zones {
+    pub hands: Vec<Hand<SortedCard>>,
+    pub draw_pile: Pile,
+    pub discard: Vec<Card>,
+    pub table: Vec<Card>,
+    deck: CardCounts,
+}
+
+impl Zones {
+    /// Puts the whole deck into the draw pile and creates empty hands for the players.
+    pub fn new(deck: Vec<Card>, players: usize) -> Self {
+        Zones {
+            hands: (0..players).map(|_| Hand::new()).collect(),
+            deck: deck.iter().copied().collect(),
+            draw_pile: Pile::of(deck),
+            discard: Vec::new(),
+            table: Vec::new(),
+        }
+    }
+
+    /// Runs the moves like [transaction], checking conservation afterwards in debug builds.
+    pub fn transaction<R, F>(&mut self, f: F) -> Result<R, ZoneError>
+    where
+        F: FnOnce(&mut Transaction<Zones>) -> Result<R, ZoneError>,
+    {
+        let result = transaction(self, f);
+        if cfg!(debug_assertions) {
+            if let Err(error) = self.check_conservation() {
+                panic!("{}", error);
+            }
+        }
+        result
+    }
+
+    pub fn check_conservation(&self) -> Result<(), ConservationError> {
+        let mut zones: Vec<&dyn Zone> = self.hands.iter().map(|hand| hand as &dyn Zone).collect();
+        zones.extend([&self.draw_pile as &dyn Zone, &self.discard, &self.table]);
+        check_conservation(&self.deck, &zones)
+    }
+}
+
+impl ZoneMap for Zones {
+    fn zone_mut(&mut self, id: ZoneId) -> Option<&mut dyn Zone> {
+        match id {
+            ZoneId::Hand(seat) => self.hands.get_mut(seat).map(|hand| hand as &mut dyn Zone),
+            ZoneId::DrawPile => Some(&mut self.draw_pile),
+            ZoneId::Discard => Some(&mut self.discard),
+            ZoneId::Table => Some(&mut self.table),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::generate_deck;
+    use crate::cards::parse::parse_cards;
+
+    #[test]
+    fn moves() {
+        let mut zones = Zones::new(generate_deck(2).unwrap(), 2);
+        zones.draw_pile.shuffle();
+        let top = zones.draw_pile.peek_n(3);
+        let dealt = zones
+            .transaction(|t| t.move_cards(ZoneId::DrawPile, ZoneId::Hand(0), Take::Top(3)))
+            .unwrap();
+        assert_eq!(dealt, top);
+        assert_eq!(zones.hands[0].len(), 3);
+
+        let played = zones
+            .transaction(|t| {
+                t.move_cards(ZoneId::Hand(0), ZoneId::Table, Take::Cards(&dealt[..2]))?;
+                t.move_cards(ZoneId::Table, ZoneId::Discard, Take::Top(1))
+            })
+            .unwrap();
+        assert_eq!(played, vec![dealt[0]]);
+        assert_eq!(zones.table, vec![dealt[1]]);
+        assert_eq!(zones.draw_pile.count_total(), 51);
+        assert!(zones.check_conservation().is_ok());
+    }
+
+    #[test]
+    fn rollback() {
+        let mut zones = Zones::new(parse_cards("As 2s 3s 4s").unwrap(), 2);
+        zones.draw_pile.shuffle();
+        let before = zones.draw_pile.peek_n(4);
+        let result = zones.transaction(|t| {
+            t.move_cards(ZoneId::DrawPile, ZoneId::Hand(0), Take::Top(2))?;
+            t.move_cards(ZoneId::DrawPile, ZoneId::Table, Take::Top(1))?;
+            t.move_cards(ZoneId::DrawPile, ZoneId::Hand(1), Take::Top(2))
+        });
+        assert_eq!(
+            result,
+            Err(ZoneError::NotEnoughCards {
+                requested: 2,
+                available: 1
+            })
+        );
+        assert_eq!(zones.draw_pile.peek_n(4), before);
+        assert!(zones.hands[0].is_empty() && zones.table.is_empty());
+
+        let missing = "Ks".parse().unwrap();
+        assert_eq!(
+            zones.transaction(|t| t.move_cards(ZoneId::DrawPile, ZoneId::Hand(5), Take::Top(1))),
+            Err(ZoneError::UnknownZone(ZoneId::Hand(5)))
+        );
+        assert_eq!(
+            zones.transaction(|t| t.move_cards(
+                ZoneId::DrawPile,
+                ZoneId::Table,
+                Take::Cards(&[missing])
+            )),
+            Err(ZoneError::CardMissing(missing))
+        );
+        assert_eq!(zones.draw_pile.peek_n(4), before);
+    }
+
+    #[test]
+    fn rollback_from_pile() {
+        let mut zones = Zones::new(parse_cards("As 2s 3s 4s 5s 6s").unwrap(), 1);
+        zones.draw_pile.shuffle();
+        zones.draw_pile.burn(2);
+        let discarded: Vec<Card> = zones.draw_pile.iter_inaccessible().collect();
+        let before = zones.draw_pile.peek_n(4);
+
+        // Takes a card from the non-accessible part, then reshuffles before failing
+        let result = zones.transaction(|t| {
+            t.move_cards(
+                ZoneId::DrawPile,
+                ZoneId::Table,
+                Take::Cards(&discarded[..1]),
+            )?;
+            t.move_cards(ZoneId::DrawPile, ZoneId::Hand(0), Take::Top(5))?;
+            t.move_cards(ZoneId::DrawPile, ZoneId::Hand(0), Take::Top(1))
+        });
+        assert!(result.is_err());
+        assert_eq!(zones.draw_pile.peek_n(4), before);
+        assert_eq!(zones.draw_pile.count_accessible(), 4);
+        assert_eq!(
+            zones.draw_pile.iter_inaccessible().collect::<Vec<_>>(),
+            discarded
+        );
+        assert!(zones.hands[0].is_empty() && zones.table.is_empty());
+    }
+
+    #[test]
+    fn conservation() {
+        let mut zones = Zones::new(parse_cards("As 2s").unwrap(), 1);
+        assert!(zones.check_conservation().is_ok());
+        zones.draw_pile = Pile::of(parse_cards("As").unwrap());
+        zones.table.push("As".parse().unwrap());
+        assert_eq!(
+            zones.check_conservation(),
+            Err(ConservationError {
+                missing: parse_cards("2s").unwrap(),
+                extra: parse_cards("As").unwrap(),
+            })
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use crate::cards::hand::HasHand;
+use crate::macau::{MacauAction, MacauGame, PlayerId};
+
+/// Picks an action for the player using a simple strategy: declare Macau whenever possible,
+/// play the first card that fits (jokers last), ask for the suit the bot holds the most of,
+/// never demand anything, and draw or pass otherwise.
+///
+/// Returns `None` if the player can't do anything right now.
+pub fn choose_action(game: &MacauGame, player_id: PlayerId) -> Option<MacauAction<'static>> {
+    let actions = game.legal_actions(player_id);
+    let player = game.get_player_by_id(player_id)?;
+
+    let preferred_suit = actions
+        .iter()
+        .filter_map(|action| match action {
+            MacauAction::ChangeSuit(suit) => Some(*suit),
+            _ => None,
+        })
+        .max_by_key(|&suit| {
+            player
+                .hand()
+                .iter()
+                .filter(|card| card.suit() == Some(suit))
+                .count()
+        });
+    if let Some(suit) = preferred_suit {
+        return Some(MacauAction::ChangeSuit(suit));
+    }
+
+    let priority = |action: &MacauAction| match action {
+        MacauAction::DeclareMacau => 0,
+        MacauAction::Demand(None) => 1,
+        MacauAction::Play(_) => 2,
+        MacauAction::PlayJoker { .. } => 3,
+        MacauAction::Draw | MacauAction::Pass => 4,
+        _ => 5,
+    };
+    actions
+        .into_iter()
+        .filter(|action| priority(action) < 5)
+        .min_by_key(priority)
+}
@@ -0,0 +1,216 @@
+use crate::cards::hand::HasHand;
+use crate::cards::pile::Pile;
+use crate::cards::Card;
+use crate::macau::variant::MacauVariant;
+use crate::macau::{
+    generate_deck_for, reveal_starting_card, seat_players, MacauGame, MacauSetupError,
+};
+
+/// Sets up a game with some of the cards chosen up front, e.g. to test a rule in a specific
+/// position. The remaining cards are dealt from the rest of the deck shuffled with the seed,
+/// so the same deal always gives the same game, and every card of the deck is still somewhere.
+///
+/// ```
+/// use cardrs::cards::parse::parse_cards;
+/// use cardrs::macau::deal::ScriptedDeal;
+/// use cardrs::macau::variant::MacauVariant;
+///
+/// let game = ScriptedDeal::new(MacauVariant::default(), vec!["Alice".into(), "Bob".into()])
+///     .hand("Alice", &parse_cards("Ks Kh").unwrap())
+///     .top_card("2h".parse().unwrap())
+///     .seed(42)
+///     .build()
+///     .unwrap();
+/// assert_eq!(game.top_card().to_string(), "♥2");
+/// ```
+pub struct ScriptedDeal {
+    variant: MacauVariant,
+    player_names: Vec<String>,
+    hands: Vec<(String, Vec<Card>)>,
+    top_card: Option<Card>,
+    seed: u64,
+}
+
+impl ScriptedDeal {
+    pub fn new(variant: MacauVariant, player_names: Vec<String>) -> Self {
+        ScriptedDeal {
+            variant,
+            player_names,
+            hands: Vec::new(),
+            top_card: None,
+            seed: 0,
+        }
+    }
+
+    /// Gives the cards to the player. The hand is filled up to the variant's initial hand size
+    /// with random cards; if more cards are given, the player gets just those.
+    pub fn hand(mut self, name: &str, cards: &[Card]) -> Self {
+        self.hands.push((name.to_string(), cards.to_vec()));
+        self
+    }
+
+    /// Starts the game with the card on top, regardless of the variant's
+    /// [StartingCardRule](crate::macau::variant::StartingCardRule).
+    pub fn top_card(mut self, card: Card) -> Self {
+        self.top_card = Some(card);
+        self
+    }
+
+    /// Sets the seed the remaining cards are shuffled with, also when the draw pile runs out
+    /// and gets reshuffled. Defaults to 0.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(self) -> Result<MacauGame, MacauSetupError> {
+        let mut players = seat_players(&self.player_names)?;
+        let mut deck = generate_deck_for(&self.variant)?;
+        let mut take = |card: Card| match deck.iter().position(|&other| other == card) {
+            Some(position) => Ok(deck.swap_remove(position)),
+            None => Err(MacauSetupError::CardNotInDeck(card)),
+        };
+
+        for (name, cards) in &self.hands {
+            let seat = self
+                .player_names
+                .iter()
+                .position(|other| other == name)
+                .ok_or_else(|| MacauSetupError::UnknownPlayer(name.clone()))?;
+            for &card in cards {
+                players[seat].deal(take(card)?);
+            }
+        }
+        let top_card = self.top_card.map(&mut take).transpose()?;
+
+        let mut pile = Pile::of(deck);
+        pile.set_seed(self.seed);
+        pile.shuffle();
+        for player in &mut players {
+            while player.hand.len() < self.variant.initial_hand as usize {
+                let card = pile.pop().ok_or(MacauSetupError::NotEnoughCards)?;
+                player.deal(card);
+            }
+        }
+
+        let game = match top_card {
+            Some(card) => MacauGame::from_deal(self.variant, players, pile, card, Vec::new()),
+            None if pile.is_empty() => return Err(MacauSetupError::NotEnoughCards),
+            None => {
+                let (card, buried) = reveal_starting_card(&self.variant, &mut pile);
+                let mut game = MacauGame::from_deal(self.variant, players, pile, card, buried);
+                game.apply_starting_effect();
+                game
+            }
+        };
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse::parse_cards;
+    use crate::cards::set::CardCounts;
+    use crate::macau::variant::StartingCardRule;
+    use crate::macau::MacauAction;
+
+    fn names() -> Vec<String> {
+        vec!["Alice".to_string(), "Bob".to_string(), "Cecil".to_string()]
+    }
+
+    fn all_cards(game: &MacauGame) -> CardCounts {
+        let mut cards: Vec<Card> = game.players.iter().flat_map(|p| p.hand.iter()).collect();
+        cards.extend(game.pile.iter_accessible());
+        cards.extend(game.pile.iter_inaccessible());
+        cards.push(game.top_card);
+        cards.into_iter().collect()
+    }
+
+    #[test]
+    fn scripted_deal() {
+        let kings = parse_cards("Ks Kh").unwrap();
+        let deal = || {
+            ScriptedDeal::new(MacauVariant::default(), names())
+                .hand("Alice", &kings)
+                .hand("Cecil", &parse_cards("2h 3h 4h 5h 6h 7h").unwrap())
+                .top_card("2s".parse().unwrap())
+                .seed(42)
+        };
+        let mut game = deal().build().unwrap();
+        assert_eq!(game.players[0].hand.len(), 5);
+        assert!(kings
+            .iter()
+            .all(|&king| game.players[0].hand.contains(king)));
+        assert_eq!(game.players[1].hand.len(), 5);
+        assert_eq!(game.players[2].hand.len(), 6);
+        assert_eq!(game.top_card.to_string(), "♠2");
+
+        let generated: CardCounts = MacauVariant::default()
+            .deck
+            .generate()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(all_cards(&game), generated);
+
+        let again = deal().build().unwrap();
+        assert_eq!(
+            again.players[1].hand.iter().collect::<Vec<_>>(),
+            game.players[1].hand.iter().collect::<Vec<_>>()
+        );
+
+        let alice = game.players[0].id;
+        game.perform_action(alice, MacauAction::Play(kings[0]))
+            .unwrap();
+        assert_eq!(game.pending_war, 5);
+    }
+
+    #[test]
+    fn scripted_top_card() {
+        let variant = MacauVariant {
+            starting_card: StartingCardRule::ApplyEffect,
+            ..MacauVariant::default()
+        };
+        let game = ScriptedDeal::new(variant, names())
+            .top_card("2s".parse().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!((game.pending_war, game.pending_block), (0, 0));
+    }
+
+    #[test]
+    fn seeded_reshuffle() {
+        let drawn = || {
+            let mut game = ScriptedDeal::new(MacauVariant::default(), names())
+                .seed(3)
+                .build()
+                .unwrap();
+            let mut drawn = game.pile.pop_n(game.pile.count_accessible());
+            game.pile.add_cards(drawn.clone());
+            drawn.extend(game.pile.pop_n(5));
+            drawn
+        };
+        assert_eq!(drawn(), drawn());
+    }
+
+    #[test]
+    fn invalid_deals() {
+        let king = parse_cards("Ks").unwrap();
+        assert_eq!(
+            ScriptedDeal::new(MacauVariant::default(), names())
+                .hand("Dave", &king)
+                .build()
+                .err(),
+            Some(MacauSetupError::UnknownPlayer("Dave".to_string()))
+        );
+        assert_eq!(
+            ScriptedDeal::new(MacauVariant::default(), names())
+                .hand("Alice", &king)
+                .top_card(king[0])
+                .build()
+                .err(),
+            Some(MacauSetupError::CardNotInDeck(king[0]))
+        );
+    }
+}
@@ -1,7 +1,15 @@
-use crate::macau::{MacauEvent, MacauGame};
+use crate::macau::{MacauEvent, MacauGame, PlayerId};
+
+type Subscriber = Box<dyn Fn(&MacauGame, &MacauEvent)>;
 
 pub struct EventManager {
-    subscribers: Vec<(u32, Box<dyn Fn(&MacauGame, &MacauEvent)>)>,
+    subscribers: Vec<(PlayerId, Subscriber)>,
+}
+
+impl Default for EventManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl EventManager {
@@ -11,7 +19,7 @@ impl EventManager {
         }
     }
 
-    pub fn subscribe<F>(&mut self, player_id: u32, subscriber: F)
+    pub fn subscribe<F>(&mut self, player_id: PlayerId, subscriber: F)
     where
         F: Fn(&MacauGame, &MacauEvent) + 'static,
     {
@@ -24,9 +32,9 @@ impl EventManager {
         }
     }
 
-    pub fn notify_customized<F>(&self, game: &MacauGame, func: F)
+    pub fn notify_customized<'a, F>(&self, game: &'a MacauGame, func: F)
     where
-        F: Fn(&MacauGame, u32) -> MacauEvent,
+        F: Fn(&'a MacauGame, PlayerId) -> MacauEvent<'a>,
     {
         for (id, subscriber) in &self.subscribers {
             let event = func(game, *id);
@@ -0,0 +1,396 @@
+use crate::cards::hand::HasHand;
+use crate::cards::Card;
+use crate::macau::variant::MacauVariant;
+use crate::macau::{seat_players, MacauError, MacauGame, MacauSetupError};
+
+/// Penalty points for the cards left in a player's hand at the end of a round.
+#[derive(Debug, Clone)]
+pub struct MacauScoring {
+    /// Points for each rank in rank order, from ace to king with the knight between the jack
+    /// and the queen.
+    pub rank_points: [u32; 14],
+    pub joker_points: u32,
+    /// Points added on top of the rank points for war cards.
+    pub war_card_bonus: u32,
+}
+
+impl Default for MacauScoring {
+    fn default() -> Self {
+        MacauScoring {
+            rank_points: [11, 2, 3, 4, 5, 6, 7, 8, 9, 10, 10, 10, 10, 10],
+            joker_points: 50,
+            war_card_bonus: 10,
+        }
+    }
+}
+
+impl MacauScoring {
+    pub fn card_points(&self, variant: &MacauVariant, card: Card) -> u32 {
+        let Some(rank) = card.rank() else {
+            return self.joker_points;
+        };
+        let mut points = self.rank_points[rank.position()];
+        if variant.is_war_card(card) {
+            points += self.war_card_bonus;
+        }
+        points
+    }
+}
+
+/// When a match ends.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MatchEnd {
+    /// After the round in which any player reaches this many penalty points.
+    TargetScore(u32),
+    /// After this many rounds.
+    Rounds(u32),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MatchEvent<'a> {
+    RoundStart {
+        round: u32,
+        first_player: &'a str,
+    },
+    /// Points scored in the round and the total scores, both indexed by seat.
+    RoundEnd {
+        round: u32,
+        points: &'a [u32],
+        scores: &'a [u32],
+    },
+    /// The match is over; the winner is the player with the fewest penalty points.
+    MatchEnd {
+        scores: &'a [u32],
+        winner: &'a str,
+    },
+}
+
+type MatchSubscriber = Box<dyn Fn(&MacauMatch, &MatchEvent)>;
+
+/// A series of [MacauGame]s played by the same players, with the first player rotating
+/// every round and penalty points accumulating until [MatchEnd] is reached.
+///
+/// Subscribers of the current game are carried over to the game of every following round.
+/// Players who leave a game don't take part in the following rounds, but keep their seat
+/// and score.
+pub struct MacauMatch {
+    variant: MacauVariant,
+    player_names: Vec<String>,
+    scoring: MacauScoring,
+    end: MatchEnd,
+    game: MacauGame,
+    round: u32,
+    scores: Vec<u32>,
+    /// Whether the player in each seat still takes part in the match.
+    seated: Vec<bool>,
+    finished: bool,
+    subscribers: Vec<MatchSubscriber>,
+}
+
+impl MacauMatch {
+    pub fn new(
+        variant: MacauVariant,
+        player_names: Vec<String>,
+        scoring: MacauScoring,
+        end: MatchEnd,
+    ) -> Result<Self, MacauSetupError> {
+        let game = MacauGame::new(variant.clone(), player_names.clone())?;
+        let scores = vec![0; player_names.len()];
+        let seated = vec![true; player_names.len()];
+        Ok(MacauMatch {
+            variant,
+            player_names,
+            scoring,
+            end,
+            game,
+            round: 1,
+            scores,
+            seated,
+            finished: false,
+            subscribers: Vec::new(),
+        })
+    }
+
+    /// Notifies the subscribers about the start of the first round and starts its game.
+    pub fn start(&self) {
+        self.notify(MatchEvent::RoundStart {
+            round: self.round,
+            first_player: &self.game.current_player().name,
+        });
+        self.game.start();
+    }
+
+    pub fn subscribe<F>(&mut self, subscriber: F)
+    where
+        F: Fn(&MacauMatch, &MatchEvent) + 'static,
+    {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    /// The game of the current round.
+    pub fn game(&self) -> &MacauGame {
+        &self.game
+    }
+
+    /// The game of the current round, e.g. to subscribe to its events.
+    /// The subscribers keep getting the events of the following rounds.
+    pub fn game_mut(&mut self) -> &mut MacauGame {
+        &mut self.game
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Total penalty points, indexed by seat.
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Scores the finished game of the current round and starts the next one,
+    /// unless the match is over. Like [MacauMatch::start], this notifies the subscribers
+    /// about the new round and starts its game.
+    pub fn finish_round(&mut self) -> Result<(), MacauError> {
+        if self.finished {
+            return Err(MacauError::GameOver);
+        }
+        if !self.game.is_finished() {
+            return Err(MacauError::NotAllowed);
+        }
+
+        let mut points = vec![0; self.player_names.len()];
+        for player in self.game.players() {
+            points[player.id.seat()] = player
+                .hand()
+                .iter()
+                .map(|card| self.scoring.card_points(&self.variant, card))
+                .sum();
+        }
+        for (score, points) in self.scores.iter_mut().zip(&points) {
+            *score += points;
+        }
+        let players = self.game.players();
+        for (seat, seated) in self.seated.iter_mut().enumerate() {
+            *seated = players.iter().any(|player| player.id.seat() == seat);
+        }
+        self.notify(MatchEvent::RoundEnd {
+            round: self.round,
+            points: &points,
+            scores: &self.scores,
+        });
+
+        let seated: Vec<usize> = (0..self.seated.len())
+            .filter(|&seat| self.seated[seat])
+            .collect();
+        self.finished = seated.len() < 2
+            || match self.end {
+                MatchEnd::TargetScore(target) => {
+                    seated.iter().any(|&seat| self.scores[seat] >= target)
+                }
+                MatchEnd::Rounds(rounds) => self.round >= rounds,
+            };
+        if self.finished {
+            let winner = seated
+                .iter()
+                .copied()
+                .min_by_key(|&seat| self.scores[seat])
+                .unwrap_or(0);
+            self.notify(MatchEvent::MatchEnd {
+                scores: &self.scores,
+                winner: &self.player_names[winner],
+            });
+            return Ok(());
+        }
+
+        // The first seat rotates, skipping players who left
+        let rotation = self.round as usize % self.player_names.len();
+        let first_player = seated
+            .iter()
+            .position(|&seat| seat >= rotation)
+            .unwrap_or(0);
+        self.round += 1;
+        let players = seat_players(&self.player_names)
+            .expect("the names were already validated for the first round")
+            .into_iter()
+            .filter(|player| self.seated[player.id.seat()])
+            .collect();
+        let mut game = MacauGame::deal_to(self.variant.clone(), players)
+            .expect("the setup was already validated for the first round")
+            .with_first_player(first_player);
+        game.event_manager = std::mem::take(&mut self.game.event_manager);
+        self.game = game;
+        self.notify(MatchEvent::RoundStart {
+            round: self.round,
+            first_player: &self.game.current_player().name,
+        });
+        self.game.start();
+        Ok(())
+    }
+
+    fn notify(&self, event: MatchEvent) {
+        for subscriber in &self.subscribers {
+            subscriber(self, &event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::hand::Hand;
+    use crate::cards::{JokerColor, Rank, Suit};
+    use crate::macau::{LeaveReason, MacauAction, MacauEvent};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn scoring_rounds() {
+        let names = vec!["Alice".to_string(), "Bob".to_string()];
+        let mut m = MacauMatch::new(
+            MacauVariant::default(),
+            names,
+            MacauScoring::default(),
+            MatchEnd::Rounds(2),
+        )
+        .unwrap();
+        assert_eq!(m.finish_round(), Err(MacauError::NotAllowed));
+
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let game = m.game_mut();
+        game.top_card = Card::new(Suit::Hearts, Rank::Five);
+        game.effective_top = game.top_card;
+        game.deck_cards = None;
+        game.players[0].hand = Hand::new();
+        game.players[0].deal(nine_h);
+        game.players[1].hand = Hand::new();
+        game.players[1].deal(Card::new(Suit::Spades, Rank::King));
+        game.players[1].deal(Card::new(Suit::Diamonds, Rank::Seven));
+        game.players[1].deal(Card::new_joker(JokerColor::Red));
+        let alice = game.players[0].id;
+        game.perform_action(alice, MacauAction::Play(nine_h))
+            .unwrap();
+
+        m.finish_round().unwrap();
+        assert_eq!(m.scores(), &[0, 20 + 7 + 50]);
+        assert_eq!(m.round(), 2);
+        assert_eq!(m.game().current_player().name, "Bob");
+        assert!(!m.is_finished());
+    }
+
+    #[test]
+    fn card_points() {
+        let variant = MacauVariant::default();
+        let mut scoring = MacauScoring::default();
+        scoring.rank_points[Rank::Knight.position()] = 12;
+        let points = |rank| scoring.card_points(&variant, Card::new(Suit::Hearts, rank));
+        assert_eq!(points(Rank::Ace), 11);
+        assert_eq!(points(Rank::Jack), 10);
+        assert_eq!(points(Rank::Knight), 12);
+        assert_eq!(points(Rank::King), 10 + 10);
+        assert_eq!(points(Rank::Two), 2 + 10);
+    }
+
+    /// Lets the player on turn go out with their only card.
+    fn go_out(game: &mut MacauGame) {
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        game.top_card = Card::new(Suit::Hearts, Rank::Five);
+        game.effective_top = game.top_card;
+        game.deck_cards = None;
+        let player = &mut game.players[game.current];
+        player.hand = Hand::new();
+        player.deal(nine_h);
+        let id = player.id;
+        game.perform_action(id, MacauAction::Play(nine_h)).unwrap();
+    }
+
+    #[test]
+    fn subscribers_carry_over() {
+        let names = vec!["Alice".to_string(), "Bob".to_string()];
+        let mut m = MacauMatch::new(
+            MacauVariant::default(),
+            names,
+            MacauScoring::default(),
+            MatchEnd::Rounds(3),
+        )
+        .unwrap();
+        let rounds = Rc::new(RefCell::new(Vec::new()));
+        let recorded = rounds.clone();
+        m.subscribe(move |_, event| {
+            if let MatchEvent::RoundStart {
+                round,
+                first_player,
+            } = event
+            {
+                recorded
+                    .borrow_mut()
+                    .push((*round, first_player.to_string()));
+            }
+        });
+        let plays = Rc::new(RefCell::new(Vec::new()));
+        let recorded = plays.clone();
+        let alice = m.game().players()[0].id;
+        m.game_mut().subscribe(alice, move |_, event| {
+            if let MacauEvent::PlayerAction { player, .. } = event {
+                recorded.borrow_mut().push(player.name.clone());
+            }
+        });
+
+        m.start();
+        go_out(m.game_mut());
+        m.finish_round().unwrap();
+        go_out(m.game_mut());
+
+        assert_eq!(
+            *rounds.borrow(),
+            vec![(1, "Alice".to_string()), (2, "Bob".to_string())]
+        );
+        assert_eq!(*plays.borrow(), vec!["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn player_leaves() {
+        let names = vec!["Alice".to_string(), "Bob".to_string(), "Cecil".to_string()];
+        let mut m = MacauMatch::new(
+            MacauVariant::default(),
+            names,
+            MacauScoring::default(),
+            MatchEnd::Rounds(3),
+        )
+        .unwrap();
+        let game = m.game_mut();
+        let bob = game.players[1].id;
+        game.remove_player(bob, LeaveReason::Left).unwrap();
+        game.deck_cards = None;
+        game.players[0].hand = Hand::new();
+        game.players[0].deal(Card::new(Suit::Spades, Rank::King));
+        game.players[0].deal(Card::new(Suit::Diamonds, Rank::Seven));
+        game.current = 1;
+        go_out(game);
+
+        m.finish_round().unwrap();
+        assert_eq!(m.scores(), &[20 + 7, 0, 0]);
+        let names: Vec<&str> = m.game().players().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Cecil"]);
+        assert_eq!(m.game().players()[1].id.seat(), 2);
+        // Bob would have been first in the second round
+        assert_eq!(m.game().current_player().name, "Cecil");
+
+        // Only the scores of players still seated can reach the target
+        m.end = MatchEnd::TargetScore(400);
+        m.scores[1] = 500;
+        go_out(m.game_mut());
+        m.finish_round().unwrap();
+        assert!(!m.is_finished());
+
+        let game = m.game_mut();
+        let cecil = game.players[1].id;
+        game.remove_player(cecil, LeaveReason::Disconnected)
+            .unwrap();
+        m.finish_round().unwrap();
+        assert!(m.is_finished());
+    }
+}
@@ -1,19 +1,57 @@
-use crate::cards::deck::generate_deck;
+use crate::cards::deck::DeckError;
 use crate::cards::hand::{Hand, HasHand, SortedCard};
 use crate::cards::pile::Pile;
-use crate::cards::Card;
+use crate::cards::set::CardCounts;
+use crate::cards::zone::{
+    check_conservation, move_cards, transaction, ConservationError, Take, Zone, ZoneError, ZoneId,
+    ZoneMap,
+};
+use crate::cards::{Card, Rank, Suit};
 use crate::macau::events::EventManager;
-use crate::macau::variant::MacauVariant;
-use std::fmt::{Debug, Formatter};
+use crate::macau::variant::{DrawRule, LastCardPenalty, MacauVariant, StartingCardRule};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
 
-mod events;
+pub mod bot;
+pub mod deal;
+pub mod events;
+pub mod matches;
+pub mod scenario;
+pub mod text;
 pub mod variant;
 
+/// Identifies a player within a single game.
+///
+/// Ids are allocated in seat order when the game is created, so they are unique within the game
+/// and keep telling the original seat even after other players leave.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PlayerId(u32);
+
+impl PlayerId {
+    /// Returns the seat the player took when the game was created.
+    pub fn seat(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Display for PlayerId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
 #[derive(Debug)]
 pub struct MacauPlayer {
-    pub id: u32,
+    pub id: PlayerId,
     pub name: String,
     pub hand: Hand<SortedCard>,
+    /// Number of upcoming turns the player has to wait after accepting a block.
+    pub turns_to_skip: u32,
+    /// Whether the player has declared Macau since their hand last changed size.
+    pub declared_macau: bool,
+    /// Whether a bot plays in this seat, see [MacauGame::play_bots].
+    pub is_bot: bool,
 }
 
 impl PartialEq for MacauPlayer {
@@ -39,16 +77,45 @@ impl HasHand for MacauPlayer {
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum MacauAction<'a> {
     Play(Card),
+    /// Plays several cards of the same rank at once. The first one has to match the top card.
     PlayMultiple(&'a [Card]),
+    /// Plays a joker standing in for `as_card`.
+    PlayJoker {
+        joker: Card,
+        as_card: Card,
+    },
+    /// Chooses the requested suit after playing an ace.
+    ChangeSuit(Suit),
+    /// Chooses the demanded rank after playing a jack, or `None` to demand nothing.
+    Demand(Option<Rank>),
+    /// Plays a card identical to the one just played, out of turn.
+    /// Only available with [MacauVariant::allow_interrupts].
+    InterruptPlay(Card),
+    /// Draws a card, or takes the war penalty.
+    /// If the pile runs out, the player draws what's left and the rest is forfeited.
     Draw,
+    /// Declares having one card left, or about to have. A player who can make an
+    /// [MacauAction::InterruptPlay] may declare before it, out of turn.
     DeclareMacau,
+    /// Accepts a block, or ends the turn without playing the card just drawn.
     Pass,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LeaveReason {
+    Left,
+    Disconnected,
+    Kicked,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum GameEndReason<'a> {
     PlayerWon(&'a MacauPlayer),
     NotEnoughPlayers,
+    /// Nobody can play and there is nothing left to draw.
+    Stalemate,
+    /// The game reached [MacauVariant::max_turns].
+    TurnLimitReached,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -56,6 +123,8 @@ pub enum MacauEvent<'a> {
     GameStart {
         players: &'a [MacauPlayer],
         top_card: Card,
+        /// Action cards revealed and buried before the top card, in order.
+        buried: &'a [Card],
         your_cards: &'a [SortedCard],
     },
     TurnStart {
@@ -68,83 +137,1540 @@ pub enum MacauEvent<'a> {
         player: &'a MacauPlayer,
         action: MacauAction<'a>,
     },
+    /// A player jumped in out of turn, taking the turn over from `interrupted`.
+    Interrupt {
+        player: &'a MacauPlayer,
+        interrupted: &'a MacauPlayer,
+        card: Card,
+    },
+    /// The player left the game and their cards were shuffled into the pile.
+    PlayerLeft {
+        player: &'a MacauPlayer,
+        reason: LeaveReason,
+    },
+    /// A bot took over the player's seat.
+    BotTookOver {
+        player: &'a MacauPlayer,
+    },
+    /// The player may play the card they just drew, or [pass](MacauAction::Pass).
+    /// `card` is only filled in for the player themselves.
+    DrawnCardPlayable {
+        player: &'a MacauPlayer,
+        card: Option<Card>,
+    },
     TurnEnd {
         player: &'a MacauPlayer,
     },
+    /// The player drew `count` cards. `cards` is only filled in for the player themselves.
     AddCards {
         player: &'a MacauPlayer,
         cards: &'a [Card],
+        count: usize,
     },
     GameEnd {
         reason: GameEndReason<'a>,
+        /// Players ordered by the number of cards left in their hands.
+        ranking: &'a [&'a MacauPlayer],
     },
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MacauError {
+    GameOver,
+    UnknownPlayer,
+    NotYourTurn,
+    CardNotInHand(Card),
+    CardDoesNotMatch(Card),
+    InvalidCombination,
+    InvalidDemand(Rank),
+    CannotFinishOn(Card),
+    ChoiceRequired,
+    NoChoicePending,
+    NotAllowed,
+    InterruptNotAllowed,
+}
+
+impl Display for MacauError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MacauError::GameOver => write!(f, "The game is already over."),
+            MacauError::UnknownPlayer => write!(f, "There is no such player in this game."),
+            MacauError::NotYourTurn => write!(f, "It's not your turn."),
+            MacauError::CardNotInHand(card) => write!(f, "You don't have {:?}.", card),
+            MacauError::CardDoesNotMatch(card) => write!(f, "{:?} can't be played now.", card),
+            MacauError::InvalidCombination => write!(f, "These cards can't be played together."),
+            MacauError::InvalidDemand(rank) => write!(f, "{} can't be demanded.", rank.name()),
+            MacauError::CannotFinishOn(card) => {
+                write!(f, "You can't go out on {:?}.", card)
+            }
+            MacauError::ChoiceRequired => write!(f, "You have to finish your choice first."),
+            MacauError::NoChoicePending => write!(f, "There is nothing to choose."),
+            MacauError::NotAllowed => write!(f, "This action isn't allowed now."),
+            MacauError::InterruptNotAllowed => write!(f, "You can't jump in now."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MacauSetupError {
+    NotEnoughPlayers,
+    DuplicateName(String),
+    /// The deck doesn't have enough cards to deal every hand and reveal the top card.
+    NotEnoughCards,
+    /// The deck lacks a rank the variant gives an effect to.
+    MissingRank(Rank),
+    InvalidDeck(DeckError),
+    /// A [ScriptedDeal](deal::ScriptedDeal) names a player who isn't in the game.
+    UnknownPlayer(String),
+    /// A [ScriptedDeal](deal::ScriptedDeal) uses more copies of the card than the deck has.
+    CardNotInDeck(Card),
+}
+
+impl Display for MacauSetupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MacauSetupError::NotEnoughPlayers => write!(f, "At least two players are needed."),
+            MacauSetupError::DuplicateName(name) => {
+                write!(f, "More than one player is called {}.", name)
+            }
+            MacauSetupError::NotEnoughCards => write!(f, "There are not enough cards to deal."),
+            MacauSetupError::MissingRank(rank) => {
+                write!(
+                    f,
+                    "The deck has no {}s, but the variant needs them.",
+                    rank.name()
+                )
+            }
+            MacauSetupError::InvalidDeck(error) => write!(f, "{}", error),
+            MacauSetupError::UnknownPlayer(name) => write!(f, "Nobody is called {}.", name),
+            MacauSetupError::CardNotInDeck(card) => {
+                write!(f, "There are no more copies of {} in the deck.", card)
+            }
+        }
+    }
+}
+
+/// Choice the player on turn has to make before their turn ends.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum PendingChoice {
+    Suit,
+    Demand,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Demand {
+    rank: Rank,
+    /// Number of turns under the demand that haven't started yet.
+    turns_left: usize,
+}
+
 pub struct MacauGame {
     variant: MacauVariant,
     pile: Pile,
     players: Vec<MacauPlayer>,
     event_manager: EventManager,
+    /// The card lying on top of the discard.
+    top_card: Card,
+    /// Cards buried before revealing the starting card.
+    buried: Vec<Card>,
+    /// The card the top card counts as. Differs from [Self::top_card] only for jokers.
+    effective_top: Card,
+    current: usize,
+    /// Number of turns finished so far.
+    turns: u32,
+    pending_war: u32,
+    pending_block: u32,
+    demand: Option<Demand>,
+    requested_suit: Option<Suit>,
+    choice: Option<PendingChoice>,
+    /// The card just drawn that the player on turn may still play.
+    drawn_playable: Option<Card>,
+    /// The card that can currently be matched out of turn, and the index of the player who played it.
+    interrupt_window: Option<(Card, usize)>,
+    finished: bool,
+    /// All cards of the deck, to check none are lost or duplicated. See [MacauGame::check_conservation].
+    deck_cards: Option<CardCounts>,
 }
 
 impl MacauGame {
-    pub fn new(variant: MacauVariant, player_names: Vec<String>) -> Self {
-        let players = player_names
-            .iter()
-            .map(|name| MacauPlayer {
-                id: rand::random(),
-                name: name.clone(),
-                hand: Hand::new(),
-            })
-            .collect();
+    pub fn new(variant: MacauVariant, player_names: Vec<String>) -> Result<Self, MacauSetupError> {
+        Self::deal_to(variant, seat_players(&player_names)?)
+    }
+
+    /// Deals the cards to the already seated players, who keep their ids.
+    fn deal_to(
+        variant: MacauVariant,
+        mut players: Vec<MacauPlayer>,
+    ) -> Result<Self, MacauSetupError> {
+        let mut pile = Pile::of(generate_deck_for(&variant)?);
+        pile.deal_round_robin(&mut players, variant.initial_hand as usize, 0)
+            .map_err(|_| MacauSetupError::NotEnoughCards)?;
+        if pile.is_empty() {
+            return Err(MacauSetupError::NotEnoughCards);
+        }
+
+        let (top_card, buried) = reveal_starting_card(&variant, &mut pile);
+        let mut game = Self::from_deal(variant, players, pile, top_card, buried);
+        game.apply_starting_effect();
+        Ok(game)
+    }
 
+    /// Sets up the game with the cards already dealt and no effects pending.
+    fn from_deal(
+        variant: MacauVariant,
+        players: Vec<MacauPlayer>,
+        pile: Pile,
+        top_card: Card,
+        buried: Vec<Card>,
+    ) -> Self {
         let mut game = MacauGame {
             variant,
-            pile: Pile::of(generate_deck(3)),
+            pile,
             players,
             event_manager: EventManager::new(),
+            top_card,
+            buried,
+            effective_top: top_card,
+            current: 0,
+            turns: 0,
+            pending_war: 0,
+            pending_block: 0,
+            demand: None,
+            requested_suit: None,
+            choice: None,
+            drawn_playable: None,
+            interrupt_window: None,
+            finished: false,
+            deck_cards: None,
+        };
+        game.deck_cards = game
+            .variant
+            .deck
+            .generate()
+            .ok()
+            .map(|deck| deck.into_iter().collect());
+        game
+    }
+
+    /// Makes the revealed starting card start a war or a block if the variant says so.
+    fn apply_starting_effect(&mut self) {
+        if self.variant.starting_card == StartingCardRule::ApplyEffect {
+            self.pending_war = self.variant.get_war_value(self.top_card) as u32;
+            if self.top_card.rank() == Some(Rank::Four) {
+                self.pending_block = 1;
+            }
+        }
+    }
+
+    /// Checks that every card of the variant's deck is in exactly one place: a hand, the pile
+    /// or the top of the discard. Games loaded from a [scenario] aren't checked,
+    /// as they don't need to use the whole deck.
+    pub fn check_conservation(&self) -> Result<(), ConservationError> {
+        let Some(deck_cards) = &self.deck_cards else {
+            return Ok(());
         };
+        let top = vec![self.top_card];
+        let mut zones: Vec<&dyn Zone> = self.players.iter().map(|p| &p.hand as &dyn Zone).collect();
+        zones.extend([&self.pile as &dyn Zone, &top]);
+        check_conservation(deck_cards, &zones)
+    }
 
-        for player in &mut game.players {
-            for _ in 0..game.variant.initial_hand {
-                player.deal(game.pile.pop().unwrap());
+    /// Panics in debug builds if a card was lost or duplicated.
+    fn debug_check_conservation(&self) {
+        if cfg!(debug_assertions) {
+            if let Err(error) = self.check_conservation() {
+                panic!("{}", error);
             }
         }
+    }
 
-        let top_card = game.pile.seek().unwrap();
+    /// Moves several cards between the players' hands and the draw pile in a [transaction],
+    /// once per action. A single card can't be half moved, so it's moved directly instead.
+    fn move_cards(&mut self, from: ZoneId, to: ZoneId, take: Take) -> Result<Vec<Card>, ZoneError> {
+        let mut zones = GameZones {
+            players: &mut self.players,
+            pile: &mut self.pile,
+        };
+        transaction(&mut zones, |t| t.move_cards(from, to, take))
+    }
 
-        let event_manager = &game.event_manager;
-        event_manager.notify_customized(&game, |game, id| MacauEvent::GameStart {
-            players: &game.players,
-            top_card,
-            your_cards: game.get_player_by_id(id).unwrap().hand().cards(),
+    /// Makes the player at the given index of [MacauGame::players] take the first turn.
+    fn with_first_player(mut self, index: usize) -> Self {
+        self.current = index % self.players.len();
+        self
+    }
+
+    pub fn subscribe<F>(&mut self, player_id: PlayerId, subscriber: F)
+    where
+        F: Fn(&MacauGame, &MacauEvent) + 'static,
+    {
+        self.event_manager.subscribe(player_id, subscriber);
+    }
+
+    /// Notifies the subscribers about the start of the game and the first turn.
+    pub fn start(&self) {
+        let top_card = self.top_card;
+        self.event_manager
+            .notify_customized(self, |game, id| MacauEvent::GameStart {
+                players: &game.players,
+                top_card,
+                buried: &game.buried,
+                your_cards: game
+                    .get_player_by_id(id)
+                    .map(|player| &player.hand().cards()[..])
+                    .unwrap_or(&[]),
+            });
+        self.notify(MacauEvent::TurnStart {
+            player: &self.players[self.current],
         });
+    }
 
-        game
+    pub fn variant(&self) -> &MacauVariant {
+        &self.variant
+    }
+
+    pub fn players(&self) -> &[MacauPlayer] {
+        &self.players
+    }
+
+    pub fn current_player(&self) -> &MacauPlayer {
+        &self.players[self.current]
+    }
+
+    pub fn top_card(&self) -> Card {
+        self.top_card
     }
 
-    fn get_player_by_id(&self, id: u32) -> Option<&MacauPlayer> {
-        self.players.iter().find(|player| player.id == id)
+    pub fn is_finished(&self) -> bool {
+        self.finished
     }
 
-    fn get_player_by_id_mut(&mut self, id: u32) -> Option<&mut MacauPlayer> {
-        self.players.iter_mut().find(|player| player.id == id)
+    /// Returns the players ordered by the number of cards left in their hands, fewest first.
+    /// Players with the same number of cards keep their seat order.
+    pub fn ranking(&self) -> Vec<&MacauPlayer> {
+        let mut ranking: Vec<&MacauPlayer> = self.players.iter().collect();
+        ranking.sort_by_key(|player| player.hand.len());
+        ranking
     }
+
+    /// Returns whether the game can't progress anymore: there is nothing to draw,
+    /// no effect that could wear off, and nobody holds a card they could play.
+    pub fn is_stalemate(&self) -> bool {
+        self.pile.is_empty()
+            && self.pending_war == 0
+            && self.pending_block == 0
+            && self.demand.is_none()
+            && self
+                .players
+                .iter()
+                .all(|player| !player.hand.iter().any(|card| self.can_play(card)))
+    }
+
+    /// Removes the player from the game, shuffling their cards into the pile.
+    ///
+    /// A war or block aimed at the player is dropped, and if it was their turn, it passes on
+    /// to the next player. The game ends with [GameEndReason::NotEnoughPlayers]
+    /// if fewer than two players remain.
+    pub fn remove_player(
+        &mut self,
+        player_id: PlayerId,
+        reason: LeaveReason,
+    ) -> Result<(), MacauError> {
+        if self.finished {
+            return Err(MacauError::GameOver);
+        }
+        let idx = self
+            .get_player_index(player_id)
+            .ok_or(MacauError::UnknownPlayer)?;
+
+        self.notify(MacauEvent::PlayerLeft {
+            player: &self.players[idx],
+            reason,
+        });
+        let hand = Take::Top(self.players[idx].hand.len());
+        self.move_cards(ZoneId::Hand(player_id.seat()), ZoneId::DrawPile, hand)
+            .expect("the whole hand can be taken");
+        self.pile.shuffle_accessible();
+        self.players.remove(idx);
+        self.debug_check_conservation();
+
+        self.interrupt_window = match self.interrupt_window {
+            Some((card, played_by)) if played_by > idx => Some((card, played_by - 1)),
+            Some((_, played_by)) if played_by == idx => None,
+            window => window,
+        };
+        if let Some(demand) = &mut self.demand {
+            demand.turns_left = demand.turns_left.min(self.players.len());
+        }
+
+        if self.players.len() < 2 {
+            self.finished = true;
+            self.notify_game_end(GameEndReason::NotEnoughPlayers);
+            return Ok(());
+        }
+
+        match idx.cmp(&self.current) {
+            Ordering::Less => self.current -= 1,
+            Ordering::Equal => {
+                self.pending_war = 0;
+                self.pending_block = 0;
+                self.choice = None;
+                self.drawn_playable = None;
+                self.current = (idx + self.players.len() - 1) % self.players.len();
+                self.advance();
+            }
+            Ordering::Greater => {}
+        }
+        Ok(())
+    }
+
+    /// Lets a bot play in the player's seat from now on.
+    pub fn hand_over_to_bot(&mut self, player_id: PlayerId) -> Result<(), MacauError> {
+        if self.finished {
+            return Err(MacauError::GameOver);
+        }
+        let idx = self
+            .get_player_index(player_id)
+            .ok_or(MacauError::UnknownPlayer)?;
+        self.players[idx].is_bot = true;
+        self.notify(MacauEvent::BotTookOver {
+            player: &self.players[idx],
+        });
+        Ok(())
+    }
+
+    /// Performs actions for bots as long as one of them is on turn.
+    pub fn play_bots(&mut self) {
+        while !self.finished && self.players[self.current].is_bot {
+            let id = self.players[self.current].id;
+            let Some(action) = bot::choose_action(self, id) else {
+                break;
+            };
+            if self.perform_action(id, action).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Returns every action the player could perform right now.
+    ///
+    /// [MacauAction::PlayMultiple] isn't listed; any card from a listed [MacauAction::Play]
+    /// can be extended with other cards of the same rank.
+    pub fn legal_actions(&self, player_id: PlayerId) -> Vec<MacauAction<'static>> {
+        let Some(idx) = self.get_player_index(player_id) else {
+            return Vec::new();
+        };
+
+        let mut cards: Vec<Card> = self.players[idx].hand.iter().collect();
+        cards.dedup();
+
+        let mut candidates = Vec::new();
+        for &card in &cards {
+            candidates.push(MacauAction::InterruptPlay(card));
+            if card.is_joker() {
+                for &suit in Suit::iter() {
+                    for &rank in self.variant.deck.ranks() {
+                        candidates.push(MacauAction::PlayJoker {
+                            joker: card,
+                            as_card: Card::new(suit, rank),
+                        });
+                    }
+                }
+            } else {
+                candidates.push(MacauAction::Play(card));
+            }
+        }
+        candidates.extend(Suit::iter().map(|&suit| MacauAction::ChangeSuit(suit)));
+        candidates.push(MacauAction::Demand(None));
+        candidates.extend(Rank::iter().map(|&rank| MacauAction::Demand(Some(rank))));
+        candidates.push(MacauAction::Draw);
+        candidates.push(MacauAction::DeclareMacau);
+        candidates.push(MacauAction::Pass);
+
+        candidates
+            .into_iter()
+            .filter(|&action| self.check_action(idx, action).is_ok())
+            .collect()
+    }
+
+    /// Validates and performs the action on behalf of the player.
+    pub fn perform_action(
+        &mut self,
+        player_id: PlayerId,
+        action: MacauAction,
+    ) -> Result<(), MacauError> {
+        let result = self.apply_action(player_id, action);
+        self.debug_check_conservation();
+        result
+    }
+
+    fn apply_action(&mut self, player_id: PlayerId, action: MacauAction) -> Result<(), MacauError> {
+        let idx = self
+            .get_player_index(player_id)
+            .ok_or(MacauError::UnknownPlayer)?;
+        self.check_action(idx, action)?;
+
+        if let MacauAction::InterruptPlay(card) = action {
+            let interrupted = self.current;
+            self.interrupt_window = None;
+            self.notify(MacauEvent::PlayerAction {
+                player: &self.players[idx],
+                action,
+            });
+            self.notify(MacauEvent::Interrupt {
+                player: &self.players[idx],
+                interrupted: &self.players[interrupted],
+                card,
+            });
+            self.current = idx;
+            self.play(idx, &[card], None);
+            return Ok(());
+        }
+
+        // Declaring Macau isn't a move, so others can still interrupt
+        if action != MacauAction::DeclareMacau {
+            self.interrupt_window = None;
+        }
+        self.notify(MacauEvent::PlayerAction {
+            player: &self.players[idx],
+            action,
+        });
+
+        match action {
+            MacauAction::Play(card) => self.play(idx, &[card], None),
+            MacauAction::PlayMultiple(cards) => self.play(idx, cards, None),
+            MacauAction::PlayJoker { joker, as_card } => self.play(idx, &[joker], Some(as_card)),
+            MacauAction::ChangeSuit(suit) => {
+                self.choice = None;
+                self.requested_suit = Some(suit);
+                self.end_turn(true);
+            }
+            MacauAction::Demand(rank) => {
+                self.choice = None;
+                self.demand = rank.map(|rank| Demand {
+                    rank,
+                    turns_left: self.players.len(),
+                });
+                self.end_turn(true);
+            }
+            MacauAction::Draw if self.pending_war > 0 => {
+                let count = std::mem::take(&mut self.pending_war) as usize;
+                self.draw_cards(idx, count);
+                self.end_turn(false);
+            }
+            MacauAction::Draw => self.draw(idx),
+            MacauAction::Pass if self.drawn_playable.take().is_some() => self.end_turn(false),
+            MacauAction::Pass => {
+                let block = std::mem::take(&mut self.pending_block);
+                self.players[idx].turns_to_skip += block - 1;
+                self.end_turn(false);
+            }
+            MacauAction::DeclareMacau => self.players[idx].declared_macau = true,
+            MacauAction::InterruptPlay(_) => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn check_action(&self, idx: usize, action: MacauAction) -> Result<(), MacauError> {
+        if self.finished {
+            return Err(MacauError::GameOver);
+        }
+
+        if let MacauAction::InterruptPlay(card) = action {
+            return self.check_interrupt(idx, card);
+        }
+
+        if idx != self.current {
+            let can_interrupt = self
+                .interrupt_window
+                .is_some_and(|(card, _)| self.check_interrupt(idx, card).is_ok());
+            if action != MacauAction::DeclareMacau || !can_interrupt {
+                return Err(MacauError::NotYourTurn);
+            }
+        }
+
+        let player = &self.players[idx];
+        match action {
+            MacauAction::ChangeSuit(_) => {
+                if self.choice != Some(PendingChoice::Suit) {
+                    return Err(MacauError::NoChoicePending);
+                }
+                Ok(())
+            }
+            MacauAction::Demand(rank) => {
+                if self.choice != Some(PendingChoice::Demand) {
+                    return Err(MacauError::NoChoicePending);
+                }
+                match rank {
+                    Some(rank) if !self.variant.is_demandable(rank) => {
+                        Err(MacauError::InvalidDemand(rank))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            MacauAction::DeclareMacau => {
+                if player.declared_macau || player.hand.len() > 2 {
+                    return Err(MacauError::NotAllowed);
+                }
+                Ok(())
+            }
+            _ if self.choice.is_some() => Err(MacauError::ChoiceRequired),
+            MacauAction::Play(card) | MacauAction::PlayJoker { joker: card, .. }
+                if self.drawn_playable.is_some_and(|drawn| drawn != card) =>
+            {
+                Err(MacauError::NotAllowed)
+            }
+            MacauAction::PlayMultiple(_) | MacauAction::Draw if self.drawn_playable.is_some() => {
+                Err(MacauError::NotAllowed)
+            }
+            MacauAction::Play(card) => self.check_play(idx, &[card]),
+            MacauAction::PlayMultiple(cards) => self.check_play(idx, cards),
+            MacauAction::PlayJoker { joker, as_card } => {
+                if !joker.is_joker() || !self.can_stand_in_for(as_card) {
+                    return Err(MacauError::InvalidCombination);
+                }
+                if !player.hand.contains(joker) {
+                    return Err(MacauError::CardNotInHand(joker));
+                }
+                if !self.fits(as_card) {
+                    return Err(MacauError::CardDoesNotMatch(as_card));
+                }
+                self.check_last_card(idx, &[joker])
+            }
+            MacauAction::Draw => {
+                if self.pending_block > 0 {
+                    return Err(MacauError::NotAllowed);
+                }
+                Ok(())
+            }
+            MacauAction::Pass => {
+                if self.pending_block == 0 && self.drawn_playable.is_none() {
+                    return Err(MacauError::NotAllowed);
+                }
+                Ok(())
+            }
+            MacauAction::InterruptPlay(_) => unreachable!(),
+        }
+    }
+
+    fn check_play(&self, idx: usize, cards: &[Card]) -> Result<(), MacauError> {
+        let Some(&first) = cards.first() else {
+            return Err(MacauError::InvalidCombination);
+        };
+        if cards
+            .iter()
+            .any(|card| !card.is_standard_card() || card.rank() != first.rank())
+        {
+            return Err(MacauError::InvalidCombination);
+        }
+
+        let hand = &self.players[idx].hand;
+        for &card in cards {
+            let needed = cards.iter().filter(|&&other| other == card).count();
+            let owned = hand.count(card);
+            if owned < needed {
+                return Err(MacauError::CardNotInHand(card));
+            }
+        }
+
+        if !self.fits(first) {
+            return Err(MacauError::CardDoesNotMatch(first));
+        }
+        self.check_last_card(idx, cards)
+    }
+
+    /// Checks whether the cards can be played if they are the last ones in the player's hand.
+    fn check_last_card(&self, idx: usize, cards: &[Card]) -> Result<(), MacauError> {
+        let Some(&last) = cards.last() else {
+            return Ok(());
+        };
+        if cards.len() == self.players[idx].hand.len()
+            && self.variant.last_card_penalty == LastCardPenalty::Forbidden
+            && !self.variant.can_finish_on(last)
+        {
+            return Err(MacauError::CannotFinishOn(last));
+        }
+        Ok(())
+    }
+
+    fn check_interrupt(&self, idx: usize, card: Card) -> Result<(), MacauError> {
+        if !self.variant.allow_interrupts || self.choice.is_some() {
+            return Err(MacauError::InterruptNotAllowed);
+        }
+        match self.interrupt_window {
+            Some((window_card, played_by)) if window_card == card && played_by != idx => {}
+            _ => return Err(MacauError::InterruptNotAllowed),
+        }
+        if !self.players[idx].hand.contains(card) {
+            return Err(MacauError::CardNotInHand(card));
+        }
+        if !self.fits(card) {
+            return Err(MacauError::CardDoesNotMatch(card));
+        }
+        self.check_last_card(idx, &[card])
+    }
+
+    /// Returns whether the standard card can be played on the current top card.
+    fn fits(&self, card: Card) -> bool {
+        let (Some(suit), Some(rank)) = (card.suit(), card.rank()) else {
+            return false;
+        };
+        let top = self.effective_top;
+        let matches_top = top.suit() == Some(suit) || top.rank() == Some(rank);
+
+        if self.pending_war > 0 {
+            return self.variant.cumulate_war && self.variant.is_war_card(card) && matches_top;
+        }
+        if self.pending_block > 0 {
+            return self.variant.cumulate_blocks && rank == Rank::Four;
+        }
+        if let Some(demand) = self.demand {
+            return rank == demand.rank || (self.variant.override_jack && rank == Rank::Jack);
+        }
+        if let Some(requested) = self.requested_suit {
+            return suit == requested || (self.variant.override_ace && rank == Rank::Ace);
+        }
+
+        matches_top
+            || top.is_joker()
+            || self.variant.is_queen_on_everything(card)
+            || self.variant.is_queen_on_everything(top)
+    }
+
+    /// Returns whether the card from a hand can be played on the current top card,
+    /// standing in for any suited card of the deck if it's a joker.
+    fn can_play(&self, card: Card) -> bool {
+        if card.is_joker() {
+            Suit::iter().any(|&suit| {
+                self.variant
+                    .deck
+                    .ranks()
+                    .iter()
+                    .any(|&rank| self.fits(Card::new(suit, rank)))
+            })
+        } else {
+            self.fits(card)
+        }
+    }
+
+    /// Returns whether a joker can stand in for the card: any suited card of the deck's ranks.
+    fn can_stand_in_for(&self, card: Card) -> bool {
+        card.suit().is_some()
+            && card
+                .rank()
+                .is_some_and(|rank| self.variant.deck.has_rank(rank))
+    }
+
+    /// Moves the cards from the player's hand onto the discard and applies their effects.
+    ///
+    /// `as_card` is the card a played joker stands in for.
+    fn play(&mut self, idx: usize, cards: &[Card], as_card: Option<Card>) {
+        let played = self.players[idx]
+            .hand
+            .take(Take::Cards(cards))
+            .expect("the played cards were checked to be in the hand");
+        for card in played {
+            let previous = std::mem::replace(&mut self.top_card, card);
+            self.pile.add_card(previous);
+        }
+        self.effective_top = as_card.unwrap_or(self.top_card);
+        self.requested_suit = None;
+        self.drawn_playable = None;
+
+        let effective = match as_card {
+            Some(card) => vec![card],
+            None => cards.to_vec(),
+        };
+        for &card in &effective {
+            self.pending_war += self.variant.get_war_value(card) as u32;
+            if card.rank() == Some(Rank::Four) {
+                self.pending_block += 1;
+            }
+        }
+
+        if self.players[idx].hand.is_empty() {
+            if let LastCardPenalty::Draw(count) = self.variant.last_card_penalty {
+                if !self.variant.can_finish_on(self.top_card) {
+                    self.draw_cards(idx, count as usize);
+                }
+            }
+        }
+        if self.players[idx].hand.is_empty() {
+            self.finish(idx);
+            return;
+        }
+
+        self.choice = match self.effective_top.rank() {
+            Some(Rank::Ace) => Some(PendingChoice::Suit),
+            Some(Rank::Jack) => Some(PendingChoice::Demand),
+            _ => None,
+        };
+        if self.choice.is_none() {
+            self.end_turn(true);
+        }
+    }
+
+    /// Draws cards for the player on turn according to [MacauVariant::draw_rule].
+    fn draw(&mut self, idx: usize) {
+        let limit = match self.variant.draw_rule {
+            DrawRule::DrawUntilPlayable { limit } => limit.max(1) as usize,
+            _ => 1,
+        };
+
+        let mut drawn = Vec::with_capacity(limit);
+        while drawn.len() < limit {
+            let hand = &mut self.players[idx].hand;
+            let Ok(cards) = move_cards(&mut self.pile, hand, Take::Top(1)) else {
+                break;
+            };
+            let card = cards[0];
+            drawn.push(card);
+            if self.can_play(card) {
+                break;
+            }
+        }
+
+        let playable = match self.variant.draw_rule {
+            DrawRule::EndTurn => None,
+            _ => drawn.last().copied().filter(|&card| self.can_play(card)),
+        };
+        self.announce_drawn(idx, drawn);
+
+        let Some(playable) = playable else {
+            self.end_turn(false);
+            return;
+        };
+        self.drawn_playable = Some(playable);
+        let id = self.players[idx].id;
+        self.event_manager
+            .notify_customized(self, |game, receiver| MacauEvent::DrawnCardPlayable {
+                player: &game.players[idx],
+                card: (receiver == id).then_some(playable),
+            });
+    }
+
+    /// Draws up to `count` cards, stopping early if the pile runs out.
+    fn draw_cards(&mut self, idx: usize, count: usize) {
+        let take = Take::Top(count.min(self.pile.count_total()));
+        let seat = self.players[idx].id.seat();
+        let drawn = self
+            .move_cards(ZoneId::DrawPile, ZoneId::Hand(seat), take)
+            .expect("no more cards are taken than the pile has");
+        self.announce_drawn(idx, drawn);
+    }
+
+    /// Notifies the subscribers about the cards the player drew.
+    fn announce_drawn(&mut self, idx: usize, drawn: Vec<Card>) {
+        let id = self.players[idx].id;
+        self.event_manager
+            .notify_customized(self, |game, receiver| MacauEvent::AddCards {
+                player: &game.players[idx],
+                cards: if receiver == id { &drawn } else { &[] },
+                count: drawn.len(),
+            });
+    }
+
+    /// Ends the turn of the current player and starts the next one.
+    ///
+    /// `played` tells whether the turn ended with a card played, opening an interrupt window for it.
+    fn end_turn(&mut self, played: bool) {
+        let idx = self.current;
+
+        let player = &self.players[idx];
+        if player.hand.len() == 1 && !player.declared_macau {
+            self.draw_cards(idx, self.variant.macau_penalty as usize);
+        }
+        let player = &mut self.players[idx];
+        if player.hand.len() != 1 {
+            player.declared_macau = false;
+        }
+
+        if played && self.variant.allow_interrupts && !self.top_card.is_joker() {
+            self.interrupt_window = Some((self.top_card, idx));
+        }
+
+        self.notify(MacauEvent::TurnEnd {
+            player: &self.players[idx],
+        });
+        self.turns += 1;
+
+        if self.is_stalemate() {
+            self.finished = true;
+            self.notify_game_end(GameEndReason::Stalemate);
+            return;
+        }
+        if self.variant.max_turns.is_some_and(|max| self.turns >= max) {
+            self.finished = true;
+            self.notify_game_end(GameEndReason::TurnLimitReached);
+            return;
+        }
+
+        self.advance();
+    }
+
+    fn advance(&mut self) {
+        loop {
+            if self.demand.is_some_and(|demand| demand.turns_left == 0) {
+                self.demand = None;
+            }
+            if let Some(demand) = &mut self.demand {
+                demand.turns_left -= 1;
+            }
+
+            self.current = (self.current + 1) % self.players.len();
+            let player = &mut self.players[self.current];
+            if player.turns_to_skip > 0 {
+                player.turns_to_skip -= 1;
+                self.notify(MacauEvent::TurnBlocked {
+                    player: &self.players[self.current],
+                });
+                continue;
+            }
+
+            self.notify(MacauEvent::TurnStart {
+                player: &self.players[self.current],
+            });
+            break;
+        }
+    }
+
+    fn finish(&mut self, winner: usize) {
+        self.finished = true;
+        self.choice = None;
+        self.notify(MacauEvent::TurnEnd {
+            player: &self.players[self.current],
+        });
+        self.notify_game_end(GameEndReason::PlayerWon(&self.players[winner]));
+    }
+
+    fn notify_game_end(&self, reason: GameEndReason) {
+        let ranking = self.ranking();
+        self.notify(MacauEvent::GameEnd {
+            reason,
+            ranking: &ranking,
+        });
+    }
+
+    fn notify(&self, event: MacauEvent) {
+        self.event_manager.notify_common(self, &event);
+    }
+
+    /// Players are always ordered by their ids, as they are allocated in seat order.
+    fn get_player_index(&self, id: PlayerId) -> Option<usize> {
+        self.players
+            .binary_search_by_key(&id, |player| player.id)
+            .ok()
+    }
+
+    fn get_player_by_id(&self, id: PlayerId) -> Option<&MacauPlayer> {
+        self.get_player_index(id).map(|idx| &self.players[idx])
+    }
+}
+
+/// The zones of a game cards move between: the players' hands, by seat, and the draw pile.
+/// The discard is the non-accessible part of the pile under the top card.
+struct GameZones<'a> {
+    players: &'a mut [MacauPlayer],
+    pile: &'a mut Pile,
+}
+
+impl ZoneMap for GameZones<'_> {
+    fn zone_mut(&mut self, id: ZoneId) -> Option<&mut dyn Zone> {
+        match id {
+            ZoneId::Hand(seat) => {
+                let idx = self
+                    .players
+                    .binary_search_by_key(&seat, |player| player.id.seat())
+                    .ok()?;
+                Some(&mut self.players[idx].hand)
+            }
+            ZoneId::DrawPile => Some(self.pile),
+            ZoneId::Discard | ZoneId::Table => None,
+        }
+    }
+}
+
+/// Checks the names and seats the players with empty hands.
+fn seat_players(player_names: &[String]) -> Result<Vec<MacauPlayer>, MacauSetupError> {
+    if player_names.len() < 2 {
+        return Err(MacauSetupError::NotEnoughPlayers);
+    }
+    for (i, name) in player_names.iter().enumerate() {
+        if player_names[..i].contains(name) {
+            return Err(MacauSetupError::DuplicateName(name.clone()));
+        }
+    }
+
+    Ok(player_names
+        .iter()
+        .enumerate()
+        .map(|(seat, name)| MacauPlayer {
+            id: PlayerId(seat as u32),
+            name: name.clone(),
+            hand: Hand::new(),
+            turns_to_skip: 0,
+            declared_macau: false,
+            is_bot: false,
+        })
+        .collect())
+}
+
+/// Generates the variant's deck, checking it has every rank the variant needs.
+fn generate_deck_for(variant: &MacauVariant) -> Result<Vec<Card>, MacauSetupError> {
+    if let Some(&rank) = variant
+        .action_ranks()
+        .iter()
+        .find(|&&rank| !variant.deck.has_rank(rank))
+    {
+        return Err(MacauSetupError::MissingRank(rank));
+    }
+    variant
+        .deck
+        .generate()
+        .map_err(MacauSetupError::InvalidDeck)
+}
+
+/// Reveals the starting card from the pile, burying action cards if the variant says so.
+fn reveal_starting_card(variant: &MacauVariant, pile: &mut Pile) -> (Card, Vec<Card>) {
+    let mut top_card = pile.pop().unwrap();
+    let mut buried = Vec::new();
+
+    if variant.starting_card == StartingCardRule::Bury {
+        while variant.is_action_card(top_card) && buried.len() < pile.count_total() {
+            buried.push(top_card);
+            pile.add_card(top_card);
+            top_card = pile.pop().unwrap();
+        }
+    }
+
+    (top_card, buried)
 }
 
 impl Debug for MacauGame {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // todo!();
         write!(
             f,
             "MacauGame {{\n\
-            {}\n",
-            self.pile,
+            {}\n\
+            Top card: {:?}\n",
+            self.pile, self.top_card,
         )?;
         for player in &self.players {
-            write!(f, "{}: {}\n", player.name, player.hand)?;
+            writeln!(f, "{}: {}", player.name, player.hand)?;
         }
         write!(f, "}}")?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::DeckSpec;
+    use crate::cards::JokerColor;
+    use crate::macau::variant::LastCardRule;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn game_with(variant: MacauVariant, hands: Vec<Vec<Card>>, top_card: Card) -> MacauGame {
+        let names = (0..hands.len()).map(|i| format!("P{}", i)).collect();
+        let mut game = MacauGame::new(variant, names).unwrap();
+        for (player, cards) in game.players.iter_mut().zip(hands) {
+            player.hand = Hand::new();
+            for card in cards {
+                player.deal(card);
+            }
+        }
+        game.top_card = top_card;
+        game.effective_top = top_card;
+        game.deck_cards = None;
+        game
+    }
+
+    fn id(game: &MacauGame, idx: usize) -> PlayerId {
+        game.players[idx].id
+    }
+
+    #[test]
+    fn play_and_draw() {
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![vec![five_h, six_s, six_s], vec![six_s, six_s, six_s]],
+            Card::new(Suit::Hearts, Rank::Nine),
+        );
+
+        let (p0, p1) = (id(&game, 0), id(&game, 1));
+        assert_eq!(
+            game.perform_action(p1, MacauAction::Draw),
+            Err(MacauError::NotYourTurn)
+        );
+        assert_eq!(
+            game.perform_action(p0, MacauAction::Play(six_s)),
+            Err(MacauError::CardDoesNotMatch(six_s))
+        );
+        assert_eq!(game.perform_action(p0, MacauAction::Play(five_h)), Ok(()));
+        assert_eq!(game.top_card(), five_h);
+        assert_eq!(game.current, 1);
+
+        assert_eq!(game.perform_action(p1, MacauAction::Draw), Ok(()));
+        assert_eq!(game.players[1].hand.len(), 4);
+        assert_eq!(game.current, 0);
+    }
+
+    #[test]
+    fn war_and_block() {
+        let two_h = Card::new(Suit::Hearts, Rank::Two);
+        let three_h = Card::new(Suit::Hearts, Rank::Three);
+        let four_c = Card::new(Suit::Clubs, Rank::Four);
+        let four_h = Card::new(Suit::Hearts, Rank::Four);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let six_c = Card::new(Suit::Clubs, Rank::Six);
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![
+                vec![two_h, four_h, six_c, six_s],
+                vec![three_h, six_s, six_s, six_s],
+                vec![six_s, six_s, six_s],
+            ],
+            Card::new(Suit::Hearts, Rank::Nine),
+        );
+        let (p0, p1, p2) = (id(&game, 0), id(&game, 1), id(&game, 2));
+
+        game.perform_action(p0, MacauAction::Play(two_h)).unwrap();
+        assert_eq!(
+            game.legal_actions(p1),
+            vec![MacauAction::Play(three_h), MacauAction::Draw]
+        );
+        game.perform_action(p1, MacauAction::Play(three_h)).unwrap();
+        game.perform_action(p2, MacauAction::Draw).unwrap();
+        assert_eq!(game.players[2].hand.len(), 8);
+        assert_eq!(game.pending_war, 0);
+
+        game.perform_action(p0, MacauAction::Play(four_h)).unwrap();
+        assert_eq!(game.legal_actions(p1), vec![MacauAction::Pass]);
+        game.players[1].deal(four_c);
+        game.perform_action(p1, MacauAction::Play(four_c)).unwrap();
+        game.perform_action(p2, MacauAction::Pass).unwrap();
+        assert_eq!(game.players[2].turns_to_skip, 1);
+
+        game.perform_action(p0, MacauAction::Play(six_c)).unwrap();
+        game.perform_action(p1, MacauAction::Play(six_s)).unwrap();
+        assert_eq!(game.current, 0);
+        assert_eq!(game.players[2].turns_to_skip, 0);
+    }
+
+    #[test]
+    fn interrupt_play() {
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let variant = MacauVariant {
+            allow_interrupts: true,
+            ..MacauVariant::default()
+        };
+        let mut game = game_with(
+            variant,
+            vec![
+                vec![five_h, six_s, six_s],
+                vec![six_s, six_s, six_s],
+                vec![five_h, six_s, six_s],
+            ],
+            Card::new(Suit::Hearts, Rank::Nine),
+        );
+        let (p0, p1, p2) = (id(&game, 0), id(&game, 1), id(&game, 2));
+
+        let interrupts = Rc::new(RefCell::new(Vec::new()));
+        let recorded = interrupts.clone();
+        game.subscribe(p0, move |_, event| {
+            if let MacauEvent::Interrupt {
+                player,
+                interrupted,
+                card,
+            } = event
+            {
+                recorded
+                    .borrow_mut()
+                    .push((player.name.clone(), interrupted.name.clone(), *card));
+            }
+        });
+
+        assert_eq!(
+            game.perform_action(p2, MacauAction::InterruptPlay(five_h)),
+            Err(MacauError::InterruptNotAllowed)
+        );
+        game.perform_action(p0, MacauAction::Play(five_h)).unwrap();
+        assert!(game
+            .legal_actions(p2)
+            .contains(&MacauAction::InterruptPlay(five_h)));
+        game.perform_action(p2, MacauAction::InterruptPlay(five_h))
+            .unwrap();
+
+        assert_eq!(
+            *interrupts.borrow(),
+            vec![("P2".to_string(), "P1".to_string(), five_h)]
+        );
+        assert_eq!(game.players[2].hand.len(), 2);
+        assert_eq!(game.current, 0);
+
+        // The window closes as soon as the player on turn acts.
+        game.players[1].deal(five_h);
+        game.perform_action(p0, MacauAction::Draw).unwrap();
+        assert_eq!(
+            game.perform_action(p1, MacauAction::InterruptPlay(five_h)),
+            Err(MacauError::InterruptNotAllowed)
+        );
+    }
+
+    #[test]
+    fn interrupt_with_macau() {
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let variant = MacauVariant {
+            allow_interrupts: true,
+            ..MacauVariant::default()
+        };
+        let mut game = game_with(
+            variant,
+            vec![
+                vec![five_h, six_s, six_s],
+                vec![six_s, six_s, six_s],
+                vec![five_h, six_s],
+            ],
+            Card::new(Suit::Hearts, Rank::Nine),
+        );
+        let (p0, p2) = (id(&game, 0), id(&game, 2));
+
+        assert_eq!(
+            game.perform_action(p2, MacauAction::DeclareMacau),
+            Err(MacauError::NotYourTurn)
+        );
+        game.perform_action(p0, MacauAction::Play(five_h)).unwrap();
+        assert_eq!(game.current, 1);
+        assert_eq!(
+            game.perform_action(p0, MacauAction::DeclareMacau),
+            Err(MacauError::NotYourTurn)
+        );
+        assert!(game.legal_actions(p2).contains(&MacauAction::DeclareMacau));
+        game.perform_action(p2, MacauAction::DeclareMacau).unwrap();
+        game.perform_action(p2, MacauAction::InterruptPlay(five_h))
+            .unwrap();
+        assert_eq!(game.players[2].hand.len(), 1);
+        assert!(game.players[2].declared_macau);
+        assert_eq!(game.current, 0);
+    }
+
+    #[test]
+    fn joker_stand_ins() {
+        let joker = Card::new_joker(JokerColor::Red);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![vec![joker, six_s], vec![six_s, six_s]],
+            nine_h,
+        );
+        game.variant.deck = DeckSpec::stripped(Rank::Nine).jokers(1);
+        let p0 = id(&game, 0);
+        let two_h = Card::new(Suit::Hearts, Rank::Two);
+        let nine_s = Card::new(Suit::Spades, Rank::Nine);
+        let actions = game.legal_actions(p0);
+        assert!(!actions.contains(&MacauAction::PlayJoker {
+            joker,
+            as_card: two_h
+        }));
+        assert!(actions.contains(&MacauAction::PlayJoker {
+            joker,
+            as_card: nine_s
+        }));
+        assert_eq!(
+            game.perform_action(
+                p0,
+                MacauAction::PlayJoker {
+                    joker,
+                    as_card: two_h
+                }
+            ),
+            Err(MacauError::InvalidCombination)
+        );
+
+        let knight_h = Card::new(Suit::Hearts, Rank::Knight);
+        let knight_s = Card::new(Suit::Spades, Rank::Knight);
+        game.variant.deck = DeckSpec::tarot().jokers(1);
+        game.top_card = knight_h;
+        game.effective_top = knight_h;
+        assert!(game.legal_actions(p0).contains(&MacauAction::PlayJoker {
+            joker,
+            as_card: knight_s
+        }));
+    }
+
+    #[test]
+    fn draw_rules() {
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let hands = || vec![vec![six_s, six_s, six_s], vec![six_s, six_s, six_s]];
+
+        let mut game = game_with(MacauVariant::default(), hands(), nine_h);
+        game.pile.add_on_top(five_h);
+        game.perform_action(id(&game, 0), MacauAction::Draw)
+            .unwrap();
+        assert_eq!(game.current, 1);
+
+        let variant = MacauVariant {
+            draw_rule: DrawRule::PlayDrawn,
+            ..MacauVariant::default()
+        };
+        let mut game = game_with(variant, hands(), nine_h);
+        let p0 = id(&game, 0);
+        game.pile.add_on_top(five_h);
+        game.perform_action(p0, MacauAction::Draw).unwrap();
+        assert_eq!(game.current, 0);
+        assert_eq!(
+            game.legal_actions(p0),
+            vec![MacauAction::Play(five_h), MacauAction::Pass]
+        );
+        game.perform_action(p0, MacauAction::Play(five_h)).unwrap();
+        assert_eq!(game.top_card(), five_h);
+
+        let variant = MacauVariant {
+            draw_rule: DrawRule::DrawUntilPlayable { limit: 3 },
+            ..MacauVariant::default()
+        };
+        let mut game = game_with(variant, hands(), nine_h);
+        let p0 = id(&game, 0);
+        game.pile.add_on_top(five_h);
+        game.pile.add_on_top(six_s);
+        game.pile.add_on_top(six_s);
+        game.perform_action(p0, MacauAction::Draw).unwrap();
+        assert_eq!(game.players[0].hand.len(), 6);
+        assert_eq!(game.drawn_playable, Some(five_h));
+        game.perform_action(p0, MacauAction::Pass).unwrap();
+        assert_eq!(game.current, 1);
+
+        let mut game = game_with(game.variant.clone(), hands(), nine_h);
+        for _ in 0..4 {
+            game.pile.add_on_top(six_s);
+        }
+        game.perform_action(id(&game, 0), MacauAction::Draw)
+            .unwrap();
+        assert_eq!(game.players[0].hand.len(), 6);
+        assert_eq!(game.current, 1);
+    }
+
+    #[test]
+    fn starting_card() {
+        let two_h = Card::new(Suit::Hearts, Rank::Two);
+        let jack_c = Card::new(Suit::Clubs, Rank::Jack);
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let variant = MacauVariant {
+            starting_card: StartingCardRule::Bury,
+            ..MacauVariant::default()
+        };
+
+        let mut pile = Pile::of(vec![Card::new(Suit::Spades, Rank::Six)]);
+        pile.add_on_top(five_h);
+        pile.add_on_top(jack_c);
+        pile.add_on_top(two_h);
+        assert_eq!(
+            reveal_starting_card(&variant, &mut pile),
+            (five_h, vec![two_h, jack_c])
+        );
+        assert_eq!(pile.count_total(), 3);
+        assert_eq!(pile.count_accessible(), 0);
+
+        let mut pile = Pile::new_empty();
+        pile.add_on_top(two_h);
+        assert_eq!(
+            reveal_starting_card(&MacauVariant::default(), &mut pile),
+            (two_h, vec![])
+        );
+    }
+
+    #[test]
+    fn last_card() {
+        let two_h = Card::new(Suit::Hearts, Rank::Two);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let variant = MacauVariant {
+            last_card: LastCardRule::NoActionCards,
+            ..MacauVariant::default()
+        };
+
+        let mut game = game_with(variant.clone(), vec![vec![two_h], vec![six_s]], nine_h);
+        let p0 = id(&game, 0);
+        assert_eq!(
+            game.perform_action(p0, MacauAction::Play(two_h)),
+            Err(MacauError::CannotFinishOn(two_h))
+        );
+        assert_eq!(
+            game.legal_actions(p0),
+            vec![MacauAction::Draw, MacauAction::DeclareMacau]
+        );
+
+        let variant = MacauVariant {
+            last_card_penalty: LastCardPenalty::Draw(3),
+            ..variant
+        };
+        let mut game = game_with(variant, vec![vec![two_h], vec![six_s]], nine_h);
+        game.perform_action(id(&game, 0), MacauAction::Play(two_h))
+            .unwrap();
+        assert!(!game.is_finished());
+        assert_eq!(game.players[0].hand.len(), 3);
+        assert_eq!(game.pending_war, 2);
+    }
+
+    #[test]
+    fn stalemate_and_turn_limit() {
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let seven_c = Card::new(Suit::Clubs, Rank::Seven);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![vec![six_s, six_s], vec![seven_c, six_s]],
+            nine_h,
+        );
+        game.pile = Pile::new_empty();
+        game.pile.add_on_top(seven_c);
+        assert!(!game.is_stalemate());
+        game.perform_action(id(&game, 0), MacauAction::Draw)
+            .unwrap();
+        assert!(game.is_stalemate());
+        assert!(game.is_finished());
+        let ranking: Vec<&str> = game.ranking().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(ranking, vec!["P1", "P0"]);
+
+        let variant = MacauVariant {
+            max_turns: Some(2),
+            ..MacauVariant::default()
+        };
+        let mut game = game_with(
+            variant,
+            vec![vec![six_s, six_s], vec![six_s, six_s]],
+            nine_h,
+        );
+        game.perform_action(id(&game, 0), MacauAction::Draw)
+            .unwrap();
+        assert!(!game.is_finished());
+        game.perform_action(id(&game, 1), MacauAction::Draw)
+            .unwrap();
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    fn remove_player() {
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let two_h = Card::new(Suit::Hearts, Rank::Two);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![
+                vec![two_h, six_s, six_s],
+                vec![six_s, six_s, six_s],
+                vec![six_s, six_s, six_s],
+            ],
+            nine_h,
+        );
+        let (p0, p1, p2) = (id(&game, 0), id(&game, 1), id(&game, 2));
+        let total = game.pile.count_total();
+
+        game.perform_action(p0, MacauAction::Play(two_h)).unwrap();
+        assert_eq!(game.pending_war, 2);
+        game.remove_player(p1, LeaveReason::Disconnected).unwrap();
+        assert_eq!(game.pile.count_total(), total + 1 + 3);
+        assert_eq!(game.pending_war, 0);
+        assert_eq!(game.current_player().id, p2);
+
+        game.remove_player(p0, LeaveReason::Left).unwrap();
+        assert!(game.is_finished());
+        assert_eq!(
+            game.remove_player(p2, LeaveReason::Kicked),
+            Err(MacauError::GameOver)
+        );
+        assert_eq!(game.hand_over_to_bot(p2), Err(MacauError::GameOver));
+    }
+
+    #[test]
+    fn bot_takes_over() {
+        let five_h = Card::new(Suit::Hearts, Rank::Five);
+        let six_s = Card::new(Suit::Spades, Rank::Six);
+        let nine_h = Card::new(Suit::Hearts, Rank::Nine);
+        let mut game = game_with(
+            MacauVariant::default(),
+            vec![vec![six_s, five_h, six_s], vec![six_s, six_s, six_s]],
+            nine_h,
+        );
+
+        game.hand_over_to_bot(id(&game, 0)).unwrap();
+        game.play_bots();
+        assert_eq!(game.top_card(), five_h);
+        assert_eq!(game.current, 1);
+    }
+
+    #[test]
+    fn conservation() {
+        let variant = MacauVariant {
+            max_turns: Some(300),
+            ..MacauVariant::default()
+        };
+        let names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let mut game = MacauGame::new(variant, names).unwrap();
+        for idx in 0..3 {
+            game.hand_over_to_bot(id(&game, idx)).unwrap();
+        }
+        game.play_bots();
+        assert!(game.is_finished());
+        assert!(game.check_conservation().is_ok());
+
+        game.players[0].deal(Card::new_joker(JokerColor::Red));
+        assert_eq!(
+            game.check_conservation().unwrap_err().extra,
+            vec![Card::new_joker(JokerColor::Red)]
+        );
+    }
+
+    #[test]
+    fn setup() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        let game = MacauGame::new(MacauVariant::default(), names(&["A", "B", "C"])).unwrap();
+        let ids: Vec<usize> = game.players.iter().map(|p| p.id.seat()).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert_eq!(game.get_player_by_id(PlayerId(2)).unwrap().name, "C");
+
+        assert_eq!(
+            MacauGame::new(MacauVariant::default(), names(&["A", "B", "A"])).err(),
+            Some(MacauSetupError::DuplicateName("A".to_string()))
+        );
+        assert_eq!(
+            MacauGame::new(MacauVariant::default(), names(&["A"])).err(),
+            Some(MacauSetupError::NotEnoughPlayers)
+        );
+        let variant = MacauVariant {
+            initial_hand: 30,
+            ..MacauVariant::default()
+        };
+        assert_eq!(
+            MacauGame::new(variant, names(&["A", "B"])).err(),
+            Some(MacauSetupError::NotEnoughCards)
+        );
+
+        let variant = MacauVariant {
+            deck: DeckSpec::of_size(36).unwrap(),
+            ..MacauVariant::default()
+        };
+        assert_eq!(
+            MacauGame::new(variant, names(&["A", "B"])).err(),
+            Some(MacauSetupError::MissingRank(Rank::Two))
+        );
+        let variant = MacauVariant {
+            initial_hand: 30,
+            deck: DeckSpec::standard().copies(2).jokers(3),
+            ..MacauVariant::default()
+        };
+        let game = MacauGame::new(variant, names(&["A", "B"])).unwrap();
+        assert_eq!(game.pile.count_total(), 110 - 60 - 1);
+    }
+}
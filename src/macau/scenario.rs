@@ -0,0 +1,371 @@
+//! A compact text format for positions in the middle of a game, used to write regression tests.
+//!
+//! ```text
+//! # Everything after a # is a comment.
+//! player Alice: Ks Kh          # one line per player in seat order, with their hand
+//! player Bob skip=1 macau: 7c  # turns left to skip and a declared Macau follow the name
+//! top: 2h                      # or `J1 as 5h` for a joker standing in for a card
+//! draw: As 2s 3d               # the draw pile, top card first
+//! discard: 5h 6h               # cards played before the top card
+//! current: Alice
+//! turns: 3
+//! war: 5                       # cards to draw for the pending war
+//! block: 1                     # turns to wait for the pending block
+//! demand: 7 2                  # demanded rank and turns left under the demand
+//! suit: h                      # suit requested with an ace
+//! choice: suit                 # or `demand`, a choice the current player still has to make
+//! drawn: 9c                    # a drawn card the current player may still play
+//! interrupt: 2h Bob            # a card that can be matched out of turn, and who played it
+//! buried: 4s 2d                # action cards buried when revealing the starting card
+//! finished: true               # whether the game is over
+//! ```
+//!
+//! Cards are written in the ASCII notation. Only the players and the top card are required,
+//! and every key but `player` can appear only once.
+//! Player names can't contain whitespace, colons or `#`. The cards aren't checked against the
+//! variant's deck, so a scenario can leave out the cards it doesn't care about.
+
+use crate::cards::notation::{Notation, NotationDisplay};
+use crate::cards::parse::{parse_cards, ParseCardError};
+use crate::cards::pile::Pile;
+use crate::cards::{Card, Rank, Suit};
+use crate::macau::variant::MacauVariant;
+use crate::macau::{seat_players, Demand, MacauGame, MacauSetupError, PendingChoice};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// Errors in a scenario, with the number of the line they are on, counting from 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScenarioError {
+    /// The line isn't in the `key: value` form, or repeats a key.
+    InvalidLine(usize),
+    UnknownKey(usize, String),
+    InvalidCard(usize, ParseCardError),
+    InvalidValue(usize, String),
+    UnknownPlayer(usize, String),
+    MissingTopCard,
+    Setup(MacauSetupError),
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::InvalidLine(line) => {
+                write!(f, "Line {} should look like `key: value`.", line)
+            }
+            ScenarioError::UnknownKey(line, key) => {
+                write!(f, "Line {}: unknown key {}.", line, key)
+            }
+            ScenarioError::InvalidCard(line, error) => write!(f, "Line {}: {}", line, error),
+            ScenarioError::InvalidValue(line, value) => {
+                write!(f, "Line {}: invalid value {}.", line, value)
+            }
+            ScenarioError::UnknownPlayer(line, name) => {
+                write!(f, "Line {}: nobody is called {}.", line, name)
+            }
+            ScenarioError::MissingTopCard => write!(f, "The scenario has no top card."),
+            ScenarioError::Setup(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for ScenarioError {}
+
+fn cards(line: usize, value: &str) -> Result<Vec<Card>, ScenarioError> {
+    parse_cards(value).map_err(|error| ScenarioError::InvalidCard(line, error))
+}
+
+fn card(line: usize, value: &str) -> Result<Card, ScenarioError> {
+    Card::from_str(value).map_err(|error| ScenarioError::InvalidCard(line, error))
+}
+
+fn parse<T: FromStr>(line: usize, value: &str) -> Result<T, ScenarioError> {
+    value
+        .parse()
+        .map_err(|_| ScenarioError::InvalidValue(line, value.to_string()))
+}
+
+fn ascii(cards: impl IntoIterator<Item = Card>) -> String {
+    let cards: Vec<String> = cards
+        .into_iter()
+        .map(|card| card.display(Notation::Ascii).to_string())
+        .collect();
+    cards.join(" ")
+}
+
+impl MacauGame {
+    /// Loads a game from the [scenario](self) format.
+    pub fn from_scenario(variant: MacauVariant, text: &str) -> Result<Self, ScenarioError> {
+        let lines: Vec<(usize, &str, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(number, line)| {
+                let (head, value) = line
+                    .split_once(':')
+                    .ok_or(ScenarioError::InvalidLine(number))?;
+                Ok((number, head.trim(), value.trim()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let is_player = |head: &str| head.split_whitespace().next() == Some("player");
+        for (i, &(number, key, _)) in lines.iter().enumerate() {
+            if !is_player(key) && lines[..i].iter().any(|&(_, other, _)| other == key) {
+                return Err(ScenarioError::InvalidLine(number));
+            }
+        }
+        let player_lines: Vec<(usize, Vec<&str>, &str)> = lines
+            .iter()
+            .filter(|&&(_, head, _)| is_player(head))
+            .map(|&(number, head, value)| (number, head.split_whitespace().collect(), value))
+            .collect();
+        let names: Vec<String> = player_lines
+            .iter()
+            .map(|(number, head, _)| {
+                head.get(1)
+                    .map(|name| name.to_string())
+                    .ok_or(ScenarioError::InvalidLine(*number))
+            })
+            .collect::<Result<_, _>>()?;
+        let mut players = seat_players(&names).map_err(ScenarioError::Setup)?;
+        for (player, (number, head, value)) in players.iter_mut().zip(&player_lines) {
+            for &option in &head[2..] {
+                match option.split_once('=') {
+                    Some(("skip", turns)) => player.turns_to_skip = parse(*number, turns)?,
+                    None if option == "macau" => player.declared_macau = true,
+                    _ => return Err(ScenarioError::UnknownKey(*number, option.to_string())),
+                }
+            }
+            for card in cards(*number, value)? {
+                player.hand.add_card(card);
+            }
+        }
+        let seat = |number: usize, name: &str| {
+            names
+                .iter()
+                .position(|other| other == name)
+                .ok_or_else(|| ScenarioError::UnknownPlayer(number, name.to_string()))
+        };
+
+        let top = lines
+            .iter()
+            .find(|(_, key, _)| *key == "top")
+            .ok_or(ScenarioError::MissingTopCard)?;
+        let (top_card, effective_top) = match top.2.split_once(" as ") {
+            Some((joker, as_card)) => (card(top.0, joker)?, card(top.0, as_card)?),
+            None => (card(top.0, top.2)?, card(top.0, top.2)?),
+        };
+
+        let mut game = MacauGame::from_deal(variant, players, Pile::new_empty(), top_card, vec![]);
+        game.effective_top = effective_top;
+        game.deck_cards = None;
+        for &(number, key, value) in &lines {
+            match key {
+                "top" => {}
+                _ if is_player(key) => {}
+                "draw" => {
+                    for card in cards(number, value)?.into_iter().rev() {
+                        game.pile.add_on_top(card);
+                    }
+                }
+                "discard" => game.pile.add_cards(cards(number, value)?),
+                "current" => game.current = seat(number, value)?,
+                "turns" => game.turns = parse(number, value)?,
+                "war" => game.pending_war = parse(number, value)?,
+                "block" => game.pending_block = parse(number, value)?,
+                "demand" => {
+                    let (rank, turns_left) = value
+                        .split_once(' ')
+                        .ok_or(ScenarioError::InvalidValue(number, value.to_string()))?;
+                    game.demand = Some(Demand {
+                        rank: parse::<Rank>(number, rank)?,
+                        turns_left: parse(number, turns_left.trim())?,
+                    });
+                }
+                "suit" => game.requested_suit = Some(parse::<Suit>(number, value)?),
+                "choice" => {
+                    game.choice = Some(match value {
+                        "suit" => PendingChoice::Suit,
+                        "demand" => PendingChoice::Demand,
+                        _ => return Err(ScenarioError::InvalidValue(number, value.to_string())),
+                    })
+                }
+                "drawn" => game.drawn_playable = Some(card(number, value)?),
+                "interrupt" => {
+                    let (played, name) = value
+                        .split_once(' ')
+                        .ok_or(ScenarioError::InvalidValue(number, value.to_string()))?;
+                    game.interrupt_window = Some((card(number, played)?, seat(number, name)?));
+                }
+                "buried" => game.buried = cards(number, value)?,
+                "finished" => game.finished = parse(number, value)?,
+                _ => return Err(ScenarioError::UnknownKey(number, key.to_string())),
+            }
+        }
+        Ok(game)
+    }
+
+    /// Writes the current position in the [scenario](self) format.
+    pub fn to_scenario(&self) -> String {
+        let mut text = String::new();
+        // Writing to a String can't fail.
+        let mut line = |args: fmt::Arguments| writeln!(text, "{}", args).unwrap();
+
+        for player in &self.players {
+            let mut head = format!("player {}", player.name);
+            if player.turns_to_skip > 0 {
+                head += &format!(" skip={}", player.turns_to_skip);
+            }
+            if player.declared_macau {
+                head += " macau";
+            }
+            line(format_args!("{}: {}", head, ascii(player.hand.iter())));
+        }
+        if self.effective_top == self.top_card {
+            line(format_args!("top: {}", ascii([self.top_card])));
+        } else {
+            line(format_args!(
+                "top: {} as {}",
+                ascii([self.top_card]),
+                ascii([self.effective_top])
+            ));
+        }
+        line(format_args!("draw: {}", ascii(self.pile.iter_accessible())));
+        line(format_args!(
+            "discard: {}",
+            ascii(self.pile.iter_inaccessible())
+        ));
+        line(format_args!("current: {}", self.players[self.current].name));
+        line(format_args!("turns: {}", self.turns));
+        if self.pending_war > 0 {
+            line(format_args!("war: {}", self.pending_war));
+        }
+        if self.pending_block > 0 {
+            line(format_args!("block: {}", self.pending_block));
+        }
+        if let Some(demand) = self.demand {
+            line(format_args!(
+                "demand: {} {}",
+                demand.rank.index(),
+                demand.turns_left
+            ));
+        }
+        if let Some(suit) = self.requested_suit {
+            line(format_args!("suit: {}", suit.letter()));
+        }
+        match self.choice {
+            Some(PendingChoice::Suit) => line(format_args!("choice: suit")),
+            Some(PendingChoice::Demand) => line(format_args!("choice: demand")),
+            None => {}
+        }
+        if let Some(drawn) = self.drawn_playable {
+            line(format_args!("drawn: {}", ascii([drawn])));
+        }
+        if let Some((card, seat)) = self.interrupt_window {
+            line(format_args!(
+                "interrupt: {} {}",
+                ascii([card]),
+                self.players[seat].name
+            ));
+        }
+        if !self.buried.is_empty() {
+            line(format_args!(
+                "buried: {}",
+                ascii(self.buried.iter().copied())
+            ));
+        }
+        if self.finished {
+            line(format_args!("finished: true"));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::macau::deal::ScriptedDeal;
+    use crate::macau::MacauAction;
+
+    #[test]
+    fn load_and_play() {
+        let scenario = "
+            # Bob has to answer a war of five with a two.
+            player Alice: Ks 7d
+            player Bob skip=0: 2h 9c   # the nine doesn't help
+            player Cecil macau: 4c
+            top: Kh
+            draw: As 2s 3d
+            discard: 5h 6h
+            current: Bob
+            war: 5
+        ";
+        let mut game = MacauGame::from_scenario(MacauVariant::default(), scenario).unwrap();
+        assert_eq!(game.pile.peek_n(3), parse_cards("As 2s 3d").unwrap());
+        assert!(game.players[2].declared_macau);
+
+        let bob = game.players[1].id;
+        game.perform_action(bob, MacauAction::Play("2h".parse().unwrap()))
+            .unwrap();
+        assert_eq!(game.pending_war, 7);
+        assert_eq!(game.players[2].name, game.current_player().name);
+    }
+
+    #[test]
+    fn round_trip() {
+        let game = ScriptedDeal::new(
+            MacauVariant::default(),
+            vec!["Alice".to_string(), "Bob".to_string()],
+        )
+        .seed(3)
+        .build()
+        .unwrap();
+        let text = game.to_scenario();
+        let loaded = MacauGame::from_scenario(MacauVariant::default(), &text).unwrap();
+        assert_eq!(loaded.to_scenario(), text);
+
+        let text = "player A: J1\nplayer B: 10h\ntop: J2 as 5s\ndraw: \ndiscard: \ncurrent: B\n\
+                    turns: 4\ndemand: 7 2\nsuit: h\nchoice: suit\ndrawn: 10h\ninterrupt: J2 A\n\
+                    buried: 2s 4d\nfinished: true\n";
+        let loaded = MacauGame::from_scenario(MacauVariant::default(), text).unwrap();
+        assert_eq!(loaded.buried, parse_cards("2s 4d").unwrap());
+        assert!(loaded.is_finished());
+        assert_eq!(loaded.to_scenario(), text);
+    }
+
+    #[test]
+    fn errors() {
+        let load = |text| MacauGame::from_scenario(MacauVariant::default(), text).err();
+        assert_eq!(
+            load("player A: Ks\nplayer B: Kh"),
+            Some(ScenarioError::MissingTopCard)
+        );
+        assert_eq!(
+            load("player A: Ks\nplayer B: Kh\ntop: 5h\ncurrent: C"),
+            Some(ScenarioError::UnknownPlayer(4, "C".to_string()))
+        );
+        assert_eq!(
+            load("player A: Ks\nplayer B: Kh\ntop 5h"),
+            Some(ScenarioError::InvalidLine(3))
+        );
+        assert_eq!(
+            load("player A: Ks\nplayer B: Kh\ntop: 5h\ncurrent: A\ntop: 6h"),
+            Some(ScenarioError::InvalidLine(5))
+        );
+        assert_eq!(
+            load("player A: Ks\nplayer B: Kh\ntop: 5h\nfinished: maybe"),
+            Some(ScenarioError::InvalidValue(4, "maybe".to_string()))
+        );
+        assert!(matches!(
+            load("player A: Ks\nplayer B: Xx\ntop: 5h"),
+            Some(ScenarioError::InvalidCard(2, _))
+        ));
+        assert_eq!(
+            load("player A: Ks\ntop: 5h"),
+            Some(ScenarioError::Setup(MacauSetupError::NotEnoughPlayers))
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use crate::cards::locale::GrammaticalCase::{Accusative, Genitive, Nominative};
+use crate::cards::locale::{English, Locale, Polish};
+use crate::cards::Rank;
+use crate::macau::{GameEndReason, LeaveReason, MacauAction, MacauEvent};
+
+/// A [Locale] that can also describe what happens in a game of Macau.
+pub trait MacauLocale: Locale {
+    fn describe(&self, event: &MacauEvent) -> String;
+}
+
+impl MacauEvent<'_> {
+    /// Returns a sentence describing the event in the given language.
+    pub fn describe(&self, locale: &dyn MacauLocale) -> String {
+        locale.describe(self)
+    }
+}
+
+impl MacauLocale for English {
+    fn describe(&self, event: &MacauEvent) -> String {
+        let card = |card| self.card_name(card, Nominative);
+        match *event {
+            MacauEvent::GameStart { top_card, .. } => {
+                format!("The game starts with the {} on top.", card(top_card))
+            }
+            MacauEvent::TurnStart { player } => format!("It's {}'s turn.", player.name),
+            MacauEvent::TurnBlocked { player } => format!("{} waits a turn.", player.name),
+            MacauEvent::PlayerAction { player, action } => {
+                let name = &player.name;
+                match action {
+                    MacauAction::Play(played) => format!("{} plays the {}.", name, card(played)),
+                    MacauAction::PlayMultiple(cards) => {
+                        let cards: Vec<String> = cards.iter().map(|&c| card(c)).collect();
+                        format!("{} plays the {}.", name, cards.join(", the "))
+                    }
+                    MacauAction::PlayJoker { joker, as_card } => format!(
+                        "{} plays the {} as the {}.",
+                        name,
+                        card(joker),
+                        card(as_card)
+                    ),
+                    MacauAction::ChangeSuit(suit) => {
+                        format!("{} asks for {}.", name, self.suit_name(suit))
+                    }
+                    MacauAction::Demand(Some(rank)) => {
+                        let article = match rank {
+                            Rank::Ace | Rank::Eight => "an",
+                            _ => "a",
+                        };
+                        let rank = self.rank_name(rank, Accusative);
+                        format!("{} demands {} {}.", name, article, rank)
+                    }
+                    MacauAction::Demand(None) => format!("{} demands nothing.", name),
+                    MacauAction::InterruptPlay(played) => {
+                        format!("{} jumps in with the {}.", name, card(played))
+                    }
+                    MacauAction::Draw => format!("{} draws.", name),
+                    MacauAction::DeclareMacau => format!("{} declares Macau!", name),
+                    MacauAction::Pass => format!("{} passes.", name),
+                }
+            }
+            MacauEvent::Interrupt {
+                player,
+                interrupted,
+                card: played,
+            } => format!(
+                "{} takes the turn over from {} with the {}.",
+                player.name,
+                interrupted.name,
+                card(played)
+            ),
+            MacauEvent::PlayerLeft { player, reason } => match reason {
+                LeaveReason::Left => format!("{} leaves the game.", player.name),
+                LeaveReason::Disconnected => format!("{} disconnects.", player.name),
+                LeaveReason::Kicked => format!("{} is kicked from the game.", player.name),
+            },
+            MacauEvent::BotTookOver { player } => {
+                format!("A bot takes over {}'s seat.", player.name)
+            }
+            MacauEvent::DrawnCardPlayable { player, card: None } => {
+                format!("{} may play the drawn card.", player.name)
+            }
+            MacauEvent::DrawnCardPlayable {
+                player,
+                card: Some(drawn),
+            } => format!("{} may play the drawn {}.", player.name, card(drawn)),
+            MacauEvent::TurnEnd { player } => format!("{} ends their turn.", player.name),
+            MacauEvent::AddCards { player, count, .. } => {
+                let cards = if count == 1 { "card" } else { "cards" };
+                format!("{} draws {} {}.", player.name, count, cards)
+            }
+            MacauEvent::GameEnd { reason, .. } => match reason {
+                GameEndReason::PlayerWon(player) => format!("{} wins!", player.name),
+                GameEndReason::NotEnoughPlayers => {
+                    "The game ends, as there are not enough players.".to_string()
+                }
+                GameEndReason::Stalemate => "The game ends in a stalemate.".to_string(),
+                GameEndReason::TurnLimitReached => {
+                    "The game ends, as the turn limit was reached.".to_string()
+                }
+            },
+        }
+    }
+}
+
+/// Returns the form of "karta" (card) used after the number.
+fn polish_cards(count: usize) -> &'static str {
+    if count == 1 {
+        "kartę"
+    } else if (2..=4).contains(&(count % 10)) && !(12..=14).contains(&(count % 100)) {
+        "karty"
+    } else {
+        "kart"
+    }
+}
+
+impl MacauLocale for Polish {
+    /// Uses the present tense throughout, so the sentences don't depend on the player's gender.
+    fn describe(&self, event: &MacauEvent) -> String {
+        let card = |card, case| self.card_name(card, case);
+        match *event {
+            MacauEvent::GameStart { top_card, .. } => format!(
+                "Gra się zaczyna. Na stosie leży {}.",
+                card(top_card, Nominative)
+            ),
+            MacauEvent::TurnStart { player } => format!("Teraz gra {}.", player.name),
+            MacauEvent::TurnBlocked { player } => format!("{} czeka turę.", player.name),
+            MacauEvent::PlayerAction { player, action } => {
+                let name = &player.name;
+                match action {
+                    MacauAction::Play(played) => {
+                        format!("{} zagrywa {}.", name, card(played, Accusative))
+                    }
+                    MacauAction::PlayMultiple(cards) => {
+                        let cards: Vec<String> =
+                            cards.iter().map(|&c| card(c, Accusative)).collect();
+                        format!("{} zagrywa {}.", name, cards.join(", "))
+                    }
+                    MacauAction::PlayJoker { joker, as_card } => format!(
+                        "{} zagrywa {} jako {}.",
+                        name,
+                        card(joker, Accusative),
+                        card(as_card, Accusative)
+                    ),
+                    MacauAction::ChangeSuit(suit) => {
+                        format!("{} zmienia kolor na {}.", name, self.suit_name(suit))
+                    }
+                    MacauAction::Demand(Some(rank)) => {
+                        format!("{} żąda {}.", name, self.rank_name(rank, Genitive))
+                    }
+                    MacauAction::Demand(None) => format!("{} niczego nie żąda.", name),
+                    MacauAction::InterruptPlay(played) => format!(
+                        "{} wchodzi poza kolejką i zagrywa {}.",
+                        name,
+                        card(played, Accusative)
+                    ),
+                    MacauAction::Draw => format!("{} dobiera.", name),
+                    MacauAction::DeclareMacau => format!("{} mówi: Makao!", name),
+                    MacauAction::Pass => format!("{} pasuje.", name),
+                }
+            }
+            MacauEvent::Interrupt {
+                player,
+                interrupted,
+                card: played,
+            } => format!(
+                "{} przerywa turę gracza {} i zagrywa {}.",
+                player.name,
+                interrupted.name,
+                card(played, Accusative)
+            ),
+            MacauEvent::PlayerLeft { player, reason } => match reason {
+                LeaveReason::Left => format!("{} opuszcza grę.", player.name),
+                LeaveReason::Disconnected => format!("{} traci połączenie.", player.name),
+                LeaveReason::Kicked => format!("Wyrzucono z gry: {}.", player.name),
+            },
+            MacauEvent::BotTookOver { player } => {
+                format!("Bot przejmuje miejsce gracza {}.", player.name)
+            }
+            MacauEvent::DrawnCardPlayable { player, card: None } => {
+                format!("{} może zagrać dobraną kartę.", player.name)
+            }
+            MacauEvent::DrawnCardPlayable {
+                player,
+                card: Some(drawn),
+            } => format!(
+                "{} może zagrać dobraną kartę: {}.",
+                player.name,
+                card(drawn, Nominative)
+            ),
+            MacauEvent::TurnEnd { player } => format!("{} kończy turę.", player.name),
+            MacauEvent::AddCards { player, count, .. } => {
+                format!("{} dobiera {} {}.", player.name, count, polish_cards(count))
+            }
+            MacauEvent::GameEnd { reason, .. } => match reason {
+                GameEndReason::PlayerWon(player) => format!("{} wygrywa!", player.name),
+                GameEndReason::NotEnoughPlayers => {
+                    "Gra się kończy, bo zostało za mało graczy.".to_string()
+                }
+                GameEndReason::Stalemate => "Gra kończy się patem.".to_string(),
+                GameEndReason::TurnLimitReached => {
+                    "Gra się kończy, bo osiągnięto limit tur.".to_string()
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::hand::Hand;
+    use crate::cards::{Card, Suit};
+    use crate::macau::{MacauPlayer, PlayerId};
+
+    #[test]
+    fn describe() {
+        let player = MacauPlayer {
+            id: PlayerId(0),
+            name: "Ala".to_string(),
+            hand: Hand::new(),
+            turns_to_skip: 0,
+            declared_macau: false,
+            is_bot: false,
+        };
+        let event = MacauEvent::PlayerAction {
+            player: &player,
+            action: MacauAction::Play(Card::new(Suit::Hearts, Rank::Queen)),
+        };
+        assert_eq!(event.describe(&English), "Ala plays the queen of hearts.");
+        assert_eq!(event.describe(&Polish), "Ala zagrywa damę kier.");
+
+        let event = MacauEvent::AddCards {
+            player: &player,
+            cards: &[],
+            count: 22,
+        };
+        assert_eq!(event.describe(&English), "Ala draws 22 cards.");
+        assert_eq!(event.describe(&Polish), "Ala dobiera 22 karty.");
+        assert_eq!(polish_cards(12), "kart");
+        assert_eq!(polish_cards(5), "kart");
+    }
+}
@@ -1,5 +1,50 @@
+use crate::cards::deck::DeckSpec;
+use crate::cards::ordering::CardOrdering;
+use crate::cards::set::CardSet;
 use crate::cards::{Card, Rank, Suit};
 
+/// What happens after a player draws a card instead of playing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DrawRule {
+    /// Drawing a card ends the turn.
+    EndTurn,
+    /// The drawn card can be played right away if it fits.
+    PlayDrawn,
+    /// The player keeps drawing until they get a card that fits, but no more than `limit` cards.
+    /// The playable card can then be played right away.
+    DrawUntilPlayable { limit: u8 },
+}
+
+/// What happens when the first card revealed on the discard is an action card.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StartingCardRule {
+    /// The card acts on the first player as if it was played by the last one.
+    /// Aces and jacks have no effect, as there is nobody to choose a suit or a rank.
+    ApplyEffect,
+    /// The card only sets the suit and rank to match.
+    IgnoreEffect,
+    /// The card is buried in the pile and another one is revealed, until a neutral card shows.
+    Bury,
+}
+
+/// Which cards a player can't go out on.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LastCardRule {
+    Any,
+    NoJokers,
+    /// No action cards, jokers included.
+    NoActionCards,
+}
+
+/// What happens when a player plays a forbidden last card.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LastCardPenalty {
+    /// The card can't be played at all.
+    Forbidden,
+    /// The card is played, but instead of going out the player draws this many cards.
+    Draw(u8),
+}
+
 #[derive(Debug, Clone)]
 pub struct MacauVariant {
     pub initial_hand: u8,
@@ -15,6 +60,24 @@ pub struct MacauVariant {
     pub queen_of_clubs_on_everything: bool,
     pub override_jack: bool,
     pub override_ace: bool,
+    /// Number of cards a player draws when they end their turn with one card without declaring Macau.
+    pub macau_penalty: u8,
+    /// Whether a player may jump in out of turn with a card identical to the one just played.
+    ///
+    /// The window for an interrupt opens when a card is played and closes as soon as the player
+    /// on turn takes any action. Interrupts are resolved in the order they arrive, so the first
+    /// valid [InterruptPlay](crate::macau::MacauAction::InterruptPlay) wins and opens a new window
+    /// for its own card. Jokers can't be used to interrupt.
+    pub allow_interrupts: bool,
+    /// What happens after drawing a card when there is no war to take.
+    pub draw_rule: DrawRule,
+    pub starting_card: StartingCardRule,
+    pub last_card: LastCardRule,
+    pub last_card_penalty: LastCardPenalty,
+    /// Number of turns after which the game ends with players ranked by their remaining cards.
+    pub max_turns: Option<u32>,
+    /// The cards the game is played with. Every rank with an effect has to be in it.
+    pub deck: DeckSpec,
 }
 
 impl Default for MacauVariant {
@@ -33,6 +96,14 @@ impl Default for MacauVariant {
             queen_of_clubs_on_everything: false,
             override_jack: true,
             override_ace: true,
+            macau_penalty: 5,
+            allow_interrupts: false,
+            draw_rule: DrawRule::EndTurn,
+            starting_card: StartingCardRule::IgnoreEffect,
+            last_card: LastCardRule::Any,
+            last_card_penalty: LastCardPenalty::Forbidden,
+            max_turns: None,
+            deck: DeckSpec::standard().jokers(3),
         }
     }
 }
@@ -64,16 +135,99 @@ impl MacauVariant {
             Some(Rank::Three) => true,
             Some(Rank::Four) => true,
             Some(Rank::Jack) => true,
-            Some(Rank::Queen) => match card.suit() {
-                Some(Suit::Spades) => self.queen_of_spades_on_everything,
-                Some(Suit::Hearts) => self.queen_of_hearts_on_everything,
-                Some(Suit::Diamonds) => self.queen_of_diamonds_on_everything,
-                Some(Suit::Clubs) => self.queen_of_clubs_on_everything,
-                _ => false,
-            },
+            Some(Rank::Queen) => self.is_queen_on_everything(card),
             Some(Rank::King) => self.get_war_value(card) > 0,
             _ if card.is_joker() => true,
             _ => false,
         }
     }
+
+    /// Returns all cards that start or continue a war in this variant.
+    pub fn war_cards(&self) -> CardSet {
+        CardSet::ALL.filter(|card| self.is_war_card(card))
+    }
+
+    /// Returns all cards that have an effect in this variant.
+    pub fn action_cards(&self) -> CardSet {
+        CardSet::ALL.filter(|card| self.is_action_card(card))
+    }
+
+    /// Returns the ranks of the action cards, which the deck needs for the variant to be playable.
+    pub fn action_ranks(&self) -> Vec<Rank> {
+        let action_cards = self.action_cards();
+        Rank::iter()
+            .copied()
+            .filter(|&rank| !action_cards.cards_of_rank(rank).is_empty())
+            .collect()
+    }
+
+    /// Returns whether the card is a queen that can be played on everything,
+    /// and on which everything can be played.
+    pub fn is_queen_on_everything(&self, card: Card) -> bool {
+        match (card.rank(), card.suit()) {
+            (Some(Rank::Queen), Some(Suit::Spades)) => self.queen_of_spades_on_everything,
+            (Some(Rank::Queen), Some(Suit::Hearts)) => self.queen_of_hearts_on_everything,
+            (Some(Rank::Queen), Some(Suit::Diamonds)) => self.queen_of_diamonds_on_everything,
+            (Some(Rank::Queen), Some(Suit::Clubs)) => self.queen_of_clubs_on_everything,
+            _ => false,
+        }
+    }
+
+    /// Returns whether a player can go out by playing this card last.
+    pub fn can_finish_on(&self, card: Card) -> bool {
+        match self.last_card {
+            LastCardRule::Any => true,
+            LastCardRule::NoJokers => !card.is_joker(),
+            LastCardRule::NoActionCards => !self.is_action_card(card),
+        }
+    }
+
+    /// Returns the ordering that keeps action cards of this variant at the end of a hand.
+    pub fn action_cards_last(&self, ordering: CardOrdering) -> CardOrdering {
+        let action_cards = self.action_cards();
+        ordering.grouped_by(move |card| action_cards.contains(card) as u8)
+    }
+
+    /// Returns whether a jack can demand the given rank.
+    pub fn is_demandable(&self, rank: Rank) -> bool {
+        (Rank::Five..=Rank::Ten).contains(&rank) && self.deck.has_rank(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse::parse_cards;
+
+    #[test]
+    fn action_cards_last() {
+        let ordering = MacauVariant::default().action_cards_last(CardOrdering::standard());
+        let mut cards = parse_cards("J1 2h Ks 7c Kd 5h").unwrap();
+        ordering.sort(&mut cards);
+        assert_eq!(cards, parse_cards("5h Kd 7c Ks 2h J1").unwrap());
+    }
+
+    #[test]
+    fn card_sets() {
+        let variant = MacauVariant::default();
+        let war_cards: CardSet = parse_cards("2s 2h 2d 2c 3s 3h 3d 3c Ks Kh")
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(variant.war_cards(), war_cards);
+        assert!(variant.war_cards().is_subset(variant.action_cards()));
+        assert_eq!(variant.action_cards().jokers(), CardSet::JOKERS);
+        assert_eq!(
+            variant.action_ranks(),
+            [
+                Rank::Ace,
+                Rank::Two,
+                Rank::Three,
+                Rank::Four,
+                Rank::Jack,
+                Rank::Queen,
+                Rank::King
+            ]
+        );
+    }
 }
@@ -1,11 +1,8 @@
-use crate::macau::variant::MacauVariant;
-use crate::macau::MacauGame;
-
-mod cards;
-mod macau;
+use cardrs::macau::variant::MacauVariant;
+use cardrs::macau::MacauGame;
 
 fn main() {
-    let game = MacauGame::new(MacauVariant::default(), vec!["Alice".into(), "Bob".into()]);
+    let game = MacauGame::new(MacauVariant::default(), vec!["Alice".into(), "Bob".into()]).unwrap();
 
     println!("{:?}", game);
 }